@@ -45,6 +45,8 @@ fn main() {
     let _full_page = page.render().unwrap();
     let _inner_page = page.render_block("Body").unwrap();
 
+    let _layouted_page = PageLayouted.render().unwrap();
+
 
     let form = Form::default();
     let _form = form.render().unwrap();
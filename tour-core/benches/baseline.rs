@@ -0,0 +1,261 @@
+//! Byte by byte lexer, as before delimiter scanning is accelerated with `memchr`.
+//!
+//! Kept only as a baseline to compare with [`Lexer`][tour_core::Lexer], it emits the same
+//! tokens for a complete template without raw blocks.
+use std::collections::VecDeque;
+use tour_core::{Delimiter, Span, Syntax, Token, TokenKind};
+
+const TRIM: u8 = b'-';
+const ESCAPE: u8 = b'\\';
+const RAW: &str = "raw";
+const ENDRAW: &str = "endraw";
+
+pub struct Lexer<'a> {
+    source: &'a str,
+    syntax: Syntax,
+    index: usize,
+    escaped: usize,
+    state: LexState,
+    location: Location,
+    pending: VecDeque<Token<'a>>,
+}
+
+enum LexState {
+    Static,
+    Expr { delim: Delimiter },
+    Raw,
+    Eof,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            syntax: Syntax::new(),
+            index: 0,
+            escaped: 0,
+            state: LexState::Static,
+            location: Location { offset: 0, line: 1, col: 1 },
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn bytes(&self) -> &'a [u8] {
+        self.source.as_bytes()
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        if start == end {
+            return;
+        }
+        let span = self.location.span(self.bytes(), start, end);
+        self.pending.push_back(Token { kind, text: &self.source[start..end], span });
+    }
+
+    fn push_trim_end(&mut self, kind: TokenKind, start: usize, end: usize) -> usize {
+        let content = start + self.bytes()[start..end].trim_ascii_end().len();
+        self.push(kind, start, content);
+        self.push(TokenKind::Trimmed, content, end);
+        content
+    }
+
+    fn lex(&mut self) {
+        match self.state {
+            LexState::Static => self.lex_static(),
+            LexState::Expr { delim } => self.lex_expr(delim),
+            LexState::Raw => self.lex_raw(),
+            LexState::Eof => {}
+        }
+    }
+
+    fn lex_static(&mut self) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = self.escaped.max(start);
+
+        loop {
+            let Some(&byte) = source.get(current) else {
+                self.push(TokenKind::Static, start, current);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            };
+
+            if byte == ESCAPE
+                && let Some((_, len)) = self.syntax.match_open(&source[current + 1..])
+            {
+                self.push(TokenKind::Static, start, current);
+                self.push(TokenKind::Escape, current, current + 1);
+                self.index = current + 1;
+                self.escaped = current + 1 + len;
+                return;
+            }
+
+            let Some((delim, len)) = self.syntax.match_open(&source[current..]) else {
+                current += 1;
+                continue;
+            };
+
+            let trim = source.get(current + len) == Some(&TRIM);
+            match trim {
+                true => _ = self.push_trim_end(TokenKind::Static, start, current),
+                false => self.push(TokenKind::Static, start, current),
+            }
+
+            self.push(TokenKind::Open(delim), current, current + len);
+            self.index = current + len;
+
+            if trim {
+                self.push(TokenKind::Trim, self.index, self.index + 1);
+                self.index += 1;
+            }
+
+            self.state = LexState::Expr { delim };
+            return;
+        }
+    }
+
+    fn lex_expr(&mut self, delim: Delimiter) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = start;
+
+        let (close, len) = loop {
+            if current == source.len() {
+                self.push_content(delim, start, current);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            }
+
+            match self.syntax.match_close(&source[current..], delim) {
+                Some((close, _)) if delim == Delimiter::Hash && close != Delimiter::Hash => {
+                    current += 1
+                }
+                Some(close) => break close,
+                None => current += 1,
+            }
+        };
+
+        let trim = current > start && source[current - 1] == TRIM;
+        let end = if trim { current - 1 } else { current };
+
+        let is_raw = delim != Delimiter::Hash && self.source[start..end].trim() == RAW;
+
+        self.push_content(delim, start, end);
+        self.push(TokenKind::Trim, end, current);
+        self.push(TokenKind::Close(close), current, current + len);
+        self.index = current + len;
+
+        if trim {
+            let rest = &source[self.index..];
+            let ws = rest.len() - rest.trim_ascii_start().len();
+            self.push(TokenKind::Trimmed, self.index, self.index + ws);
+            self.index += ws;
+        }
+
+        self.state = match is_raw {
+            true => LexState::Raw,
+            false => LexState::Static,
+        };
+    }
+
+    fn push_content(&mut self, delim: Delimiter, start: usize, end: usize) {
+        if delim == Delimiter::Hash {
+            self.push(TokenKind::Comment, start, end);
+            return;
+        }
+
+        let content = &self.bytes()[start..end];
+        let expr_start = end - content.trim_ascii_start().len();
+        let expr_end = start + content.trim_ascii_end().len().max(expr_start - start);
+
+        self.push(TokenKind::Whitespace, start, expr_start);
+        self.push(TokenKind::Expr, expr_start, expr_end);
+        self.push(TokenKind::Whitespace, expr_end, end);
+    }
+
+    fn lex_raw(&mut self) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = start;
+
+        loop {
+            if current == source.len() {
+                self.push(TokenKind::Raw, start, current);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            }
+
+            if let Some(trim) = self.match_endraw(&source[current..]) {
+                match trim {
+                    true => _ = self.push_trim_end(TokenKind::Raw, start, current),
+                    false => self.push(TokenKind::Raw, start, current),
+                }
+                self.index = current;
+                self.state = LexState::Static;
+                return;
+            }
+
+            current += 1;
+        }
+    }
+
+    fn match_endraw(&self, source: &[u8]) -> Option<bool> {
+        let (open, len) = self.syntax.match_open(source)?;
+
+        let (trim, rest) = match &source[len..] {
+            [TRIM, rest @ ..] => (true, rest),
+            rest => (false, rest),
+        };
+        let rest = rest.trim_ascii_start().strip_prefix(ENDRAW.as_bytes())?;
+        let rest = rest.trim_ascii_start();
+        let rest = rest.strip_prefix(&[TRIM]).unwrap_or(rest);
+
+        match self.syntax.match_close(rest, open)? {
+            (close, _) if close == open => Some(trim),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if let LexState::Eof = self.state {
+                return None;
+            }
+            self.lex();
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Line and column counted byte by byte.
+struct Location {
+    offset: usize,
+    line: u32,
+    col: u32,
+}
+
+impl Location {
+    fn span(&mut self, source: &[u8], start: usize, end: usize) -> Span {
+        for &byte in &source[self.offset..start] {
+            match byte {
+                b'\n' => {
+                    self.line += 1;
+                    self.col = 1;
+                }
+                _ if byte & 0xC0 == 0x80 => {}
+                _ => self.col += 1,
+            }
+        }
+
+        self.offset = start;
+
+        Span { start, end, line: self.line, col: self.col }
+    }
+}
@@ -0,0 +1,38 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use std::hint::black_box;
+use tour_core::{Lexer, Parser, StaticVisitor};
+
+mod baseline;
+
+/// Real world page, repeated to simulate large templates.
+fn page(repeat: usize) -> String {
+    include_str!("page.html").repeat(repeat)
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    for repeat in [1, 64] {
+        let source = page(repeat);
+        assert!(Lexer::new(&source).eq(baseline::Lexer::new(&source)));
+
+        group.throughput(Throughput::Bytes(source.len() as u64));
+
+        group.bench_function(format!("static_visitor/{}KB", source.len() / 1024), |b| {
+            b.iter(|| Parser::new(black_box(&source), StaticVisitor::new()).parse().unwrap())
+        });
+
+        group.bench_function(format!("lexer/{}KB", source.len() / 1024), |b| {
+            b.iter(|| Lexer::new(black_box(&source)).count())
+        });
+
+        group.bench_function(format!("lexer_baseline/{}KB", source.len() / 1024), |b| {
+            b.iter(|| baseline::Lexer::new(black_box(&source)).count())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -1,21 +1,139 @@
+use alloc::string::String;
+
+use crate::{Delimiter, Span};
 
 /// [`Result`][std::result::Result] alias for [`ParseError`].
 pub type Result<T,E = ParseError> = core::result::Result<T,E>;
 
 /// An error that may occur during parsing in [`Parser`][super::Parser].
+///
+/// # Example
+///
+/// ```
+/// use tour_core::{Parser, StaticVisitor};
+///
+/// let source = "<p>\n  {{ name </p>";
+///
+/// let Err(err) = Parser::new(source, StaticVisitor::new()).parse() else { unreachable!() };
+/// let span = err.span().unwrap();
+///
+/// assert_eq!((span.line, span.col), (2, 3));
+/// assert_eq!(&source[span.range()], "{{ name </p>");
+/// ```
 #[derive(Debug)]
 pub enum ParseError {
+    /// Expression is not closed until the end of input.
+    Unclosed {
+        delim: Delimiter,
+        span: Span,
+    },
+    /// Expression is closed with different delimiter.
+    Mismatch {
+        open: Delimiter,
+        close: Delimiter,
+        span: Span,
+    },
+    /// Error with the location of the offending source.
+    Spanned {
+        message: String,
+        span: Span,
+    },
+    /// Error without location.
     Generic(String),
 }
 
-impl std::error::Error for ParseError {}
+impl ParseError {
+    /// Returns the location of the error, if any.
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Self::Unclosed { span, .. } => Some(*span),
+            Self::Mismatch { span, .. } => Some(*span),
+            Self::Spanned { span, .. } => Some(*span),
+            Self::Generic(_) => None,
+        }
+    }
+
+    /// Attach location to the error if it does not have one yet.
+    pub fn with_span(self, span: Span) -> Self {
+        match self {
+            Self::Generic(message) => Self::Spanned { message, span },
+            err => err,
+        }
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+impl core::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unclosed { delim, span } => {
+                write!(f, "unclosed `{delim}` expression at {span}")
+            },
+            Self::Mismatch { open, close, span } => write!(
+                f,
+                "delimiter should be same, open `{open}` closed with `{close}` at {span}"
+            ),
+            Self::Spanned { message, span } => write!(f, "{message} at {span}"),
+            Self::Generic(s) => f.write_str(s),
+        }
+    }
+}
+
+/// An error that may occur when parsing from [`io::Read`][std::io::Read] in
+/// [`StreamParser::read`][super::StreamParser::read].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum ReadError {
+    /// Template is invalid.
+    Parse(ParseError),
+    /// Reading the input failed.
+    Io(std::io::Error),
+}
+
+#[cfg(feature = "std")]
+impl ReadError {
+    /// Convert error to [`io::Error`][std::io::Error].
+    ///
+    /// [`ParseError`] will become [`io::ErrorKind::InvalidData`][std::io::ErrorKind::InvalidData].
+    pub fn into_io(self) -> std::io::Error {
+        match self {
+            Self::Parse(err) => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+            Self::Io(err) => err,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl core::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Io(err) => Some(err),
+        }
+    }
+}
 
-impl std::fmt::Display for ParseError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+#[cfg(feature = "std")]
+impl core::fmt::Display for ReadError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            ParseError::Generic(s) => f.write_str(s),
+            Self::Parse(err) => err.fmt(f),
+            Self::Io(err) => err.fmt(f),
         }
     }
 }
 
+#[cfg(feature = "std")]
+impl From<ParseError> for ReadError {
+    fn from(value: ParseError) -> Self {
+        Self::Parse(value)
+    }
+}
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for ReadError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,549 @@
+use alloc::collections::VecDeque;
+
+use crate::{Delimiter, Span, Syntax, span::Location};
+
+/// Whitespace control marker, e.g. `{{-` or `-}}`.
+pub(crate) const TRIM: u8 = b'-';
+
+/// Escape for opening delimiter, e.g. `\{{`.
+pub(crate) const ESCAPE: u8 = b'\\';
+
+/// Raw block keyword, e.g. `{{ raw }}`.
+pub(crate) const RAW: &str = "raw";
+
+/// Raw block closing keyword, e.g. `{{ endraw }}`.
+pub(crate) const ENDRAW: &str = "endraw";
+
+/// Kind of a [`Token`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Static content.
+    Static,
+    /// Whitespace in static content removed by whitespace control.
+    Trimmed,
+    /// Backslash escaping an opening delimiter, or escaping another backslash before it.
+    Escape,
+    /// Opening delimiter, e.g. `{{`.
+    Open(Delimiter),
+    /// Whitespace control marker, `-`.
+    Trim,
+    /// Whitespace between delimiter and expression.
+    Whitespace,
+    /// Expression, e.g. `name` in `{{ name }}`.
+    Expr,
+    /// Keyword opening a raw block, `raw` in `{{ raw }}`.
+    ///
+    /// It is only a raw block when followed by `{{ endraw }}`, otherwise `raw` is an [`Expr`][TokenKind::Expr].
+    RawOpen,
+    /// Comment content, e.g. ` note ` in `{# note #}`.
+    Comment,
+    /// Closing delimiter, e.g. `}}`.
+    ///
+    /// Closing delimiter is not guaranteed to be the same kind as the opening one.
+    Close(Delimiter),
+    /// Content of a raw block.
+    Raw,
+}
+
+/// A single lexical unit of template source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub span: Span,
+}
+
+/// Lossless template tokenizer.
+///
+/// Every byte of the source belongs to exactly one [`Token`], so concatenating all token texts
+/// will produce the exact source. [`Lexer`] never fails, invalid template like unclosed
+/// expression or mismatched delimiter are reported by [`Parser`][crate::Parser].
+///
+/// # Example
+///
+/// ```
+/// use tour_core::{Delimiter, Lexer, TokenKind};
+///
+/// let source = "Hello {{- name }}!";
+///
+/// let tokens = Lexer::new(source).collect::<Vec<_>>();
+/// let kinds = tokens.iter().map(|e|e.kind).collect::<Vec<_>>();
+///
+/// assert_eq!(&kinds[..], &[
+///     TokenKind::Static,
+///     TokenKind::Trimmed,
+///     TokenKind::Open(Delimiter::Brace),
+///     TokenKind::Trim,
+///     TokenKind::Whitespace,
+///     TokenKind::Expr,
+///     TokenKind::Whitespace,
+///     TokenKind::Close(Delimiter::Brace),
+///     TokenKind::Static,
+/// ]);
+///
+/// assert_eq!(tokens.iter().map(|e|e.text).collect::<String>(), source);
+/// ```
+pub struct Lexer<'a> {
+    source: &'a str,
+    syntax: Syntax,
+
+    // lexer states
+    index: usize,
+    /// opening delimiter before this index is escaped, or already scanned
+    escaped: usize,
+    state: LexState,
+    location: Location,
+    pending: VecDeque<Token<'a>>,
+
+    // streaming states
+    /// byte offset of `source` in the whole template
+    offset: usize,
+    /// `source` is not the end of the template, stop lexing instead of emitting incomplete tokens
+    partial: bool,
+    suspended: bool,
+    /// bytes required to decide a token in partial mode
+    lookahead: usize,
+
+    // bytes that may start a delimiter
+    open: Needle,
+    close: Needle,
+}
+
+#[derive(Clone, Copy)]
+enum LexState {
+    Static,
+    Expr { delim: Delimiter },
+    Raw,
+    Eof,
+}
+
+/// Lexer state to resume lexing the rest of the template.
+#[derive(Clone, Copy)]
+pub(crate) struct Checkpoint {
+    state: LexState,
+    scanned: usize,
+    offset: usize,
+    line: u32,
+    col: u32,
+}
+
+impl Checkpoint {
+    pub(crate) fn new() -> Self {
+        Self { state: LexState::Static, scanned: 0, offset: 0, line: 1, col: 1 }
+    }
+
+    /// Byte offset in the whole template.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'a> Lexer<'a> {
+    /// Create new [`Lexer`].
+    pub fn new(source: &'a str) -> Self {
+        Self::resume(source, Syntax::new(), Checkpoint::new(), false)
+    }
+
+    /// Continue lexing from [`Checkpoint`], where `source` starts at the checkpoint.
+    ///
+    /// If `partial`, lexing is suspended when the rest of `source` may be an incomplete token.
+    pub(crate) fn resume(source: &'a str, syntax: Syntax, checkpoint: Checkpoint, partial: bool) -> Self {
+        Self {
+            source,
+            index: 0,
+            escaped: checkpoint.scanned,
+            state: checkpoint.state,
+            location: Location::resume(checkpoint.line, checkpoint.col),
+            pending: VecDeque::new(),
+            offset: checkpoint.offset,
+            partial,
+            suspended: false,
+            // escaped backslash followed by delimiter, or delimiter followed by whitespace control
+            lookahead: syntax.max_len() + 2,
+            open: Needle::open(&syntax),
+            close: Needle::close(&syntax),
+            syntax,
+        }
+    }
+
+    /// Returns the number of bytes lexed, and the [`Checkpoint`] to continue from there.
+    pub(crate) fn checkpoint(&mut self) -> (usize, Checkpoint) {
+        let index = self.index;
+        let span = self.location.span(self.bytes(), index, index);
+        let checkpoint = Checkpoint {
+            state: self.state,
+            scanned: self.escaped.saturating_sub(index),
+            offset: self.offset + index,
+            line: span.line,
+            col: span.col,
+        };
+        (index, checkpoint)
+    }
+
+    /// Use given delimiter [`Syntax`].
+    pub fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.lookahead = syntax.max_len() + 2;
+        self.open = Needle::open(&syntax);
+        self.close = Needle::close(&syntax);
+        self.syntax = syntax;
+        self
+    }
+
+    /// Returns the template source.
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    fn bytes(&self) -> &'a [u8] {
+        self.source.as_bytes()
+    }
+
+    fn push(&mut self, kind: TokenKind, start: usize, end: usize) {
+        if start == end {
+            return;
+        }
+        let mut span = self.location.span(self.bytes(), start, end);
+        span.start += self.offset;
+        span.end += self.offset;
+        self.pending.push_back(Token { kind, text: &self.source[start..end], span });
+    }
+
+    /// Push whitespace trimmed content, returns the end of content.
+    fn push_trim_end(&mut self, kind: TokenKind, start: usize, end: usize) -> usize {
+        let content = start + self.bytes()[start..end].trim_ascii_end().len();
+        self.push(kind, start, content);
+        self.push(TokenKind::Trimmed, content, end);
+        content
+    }
+
+    /// In partial mode, returns `true` if token at `current` may be incomplete.
+    ///
+    /// Lexing is suspended and will be continued from the start of the current token.
+    fn suspend(&mut self, current: usize) -> bool {
+        self.suspended = self.partial && self.source.len() - current < self.lookahead;
+        self.suspended
+    }
+
+    fn lex(&mut self) {
+        match self.state {
+            LexState::Static => self.lex_static(),
+            LexState::Expr { delim } => self.lex_expr(delim),
+            LexState::Raw => self.lex_raw(),
+            LexState::Eof => {}
+        }
+    }
+
+    fn lex_static(&mut self) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = self.escaped.max(start);
+
+        loop {
+            let Some(&byte) = self.open.find(source, current).and_then(|i| {
+                current = i;
+                source.get(i)
+            }) else {
+                current = source.len();
+                if self.suspend(current) {
+                    self.escaped = current;
+                    return;
+                }
+                self.push(TokenKind::Static, start, current);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            };
+
+            if self.suspend(current) {
+                self.escaped = current;
+                return;
+            }
+
+            // `\\{{` escaped backslash, followed by a real opening delimiter
+            if byte == ESCAPE
+                && source.get(current + 1) == Some(&ESCAPE)
+                && self.syntax.match_open(&source[current + 2..]).is_some()
+            {
+                self.push(TokenKind::Static, start, current + 1);
+                self.push(TokenKind::Escape, current + 1, current + 2);
+                self.index = current + 2;
+                self.escaped = current + 2;
+                return;
+            }
+
+            // `\{{` escaped opening delimiter
+            if byte == ESCAPE
+                && let Some((_, len)) = self.syntax.match_open(&source[current + 1..])
+            {
+                self.push(TokenKind::Static, start, current);
+                self.push(TokenKind::Escape, current, current + 1);
+                self.index = current + 1;
+                self.escaped = current + 1 + len;
+                return;
+            }
+
+            let Some((delim, len)) = self.syntax.match_open(&source[current..]) else {
+                current += 1;
+                continue;
+            };
+
+            // `{{-` trim whitespace before the expression
+            let trim = source.get(current + len) == Some(&TRIM);
+            match trim {
+                true => _ = self.push_trim_end(TokenKind::Static, start, current),
+                false => self.push(TokenKind::Static, start, current),
+            }
+
+            self.push(TokenKind::Open(delim), current, current + len);
+            self.index = current + len;
+
+            if trim {
+                self.push(TokenKind::Trim, self.index, self.index + 1);
+                self.index += 1;
+            }
+
+            self.state = LexState::Expr { delim };
+            return;
+        }
+    }
+
+    fn lex_expr(&mut self, delim: Delimiter) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = start;
+
+        let (close, len) = loop {
+            current = self.close.find(source, current).unwrap_or(source.len());
+
+            if self.suspend(current) {
+                return;
+            }
+
+            if current == source.len() {
+                // unclosed expression
+                self.push_content(delim, start, current, false);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            }
+
+            match self.syntax.match_close(&source[current..], delim) {
+                // comment may contain anything except its own closing delimiter
+                Some((close, _)) if delim == Delimiter::Hash && close != Delimiter::Hash => {
+                    current += 1
+                }
+                Some(close) => break close,
+                None => current += 1,
+            }
+        };
+
+        // `-}}` trim whitespace after the expression
+        let trim = current > start && source[current - 1] == TRIM;
+        let end = if trim { current - 1 } else { current };
+
+        // trimmed whitespace may continue in the rest of the template
+        if trim && self.partial && source[current + len..].trim_ascii_start().is_empty() {
+            self.suspended = true;
+            return;
+        }
+
+        // `raw` without the matching `endraw` is a regular expression
+        let is_raw = delim != Delimiter::Hash
+            && self.source[start..end].trim() == RAW
+            && match self.find_endraw(current + len) {
+                Some(_) => true,
+                None if self.partial => {
+                    // `endraw` may be in the rest of the template
+                    self.suspended = true;
+                    return;
+                }
+                None => false,
+            };
+
+        self.push_content(delim, start, end, is_raw);
+        self.push(TokenKind::Trim, end, current);
+        self.push(TokenKind::Close(close), current, current + len);
+        self.index = current + len;
+
+        if trim {
+            let rest = &source[self.index..];
+            let ws = rest.len() - rest.trim_ascii_start().len();
+            self.push(TokenKind::Trimmed, self.index, self.index + ws);
+            self.index += ws;
+        }
+
+        self.state = match is_raw {
+            true => LexState::Raw,
+            false => LexState::Static,
+        };
+    }
+
+    /// Push expression or comment content.
+    fn push_content(&mut self, delim: Delimiter, start: usize, end: usize, is_raw: bool) {
+        if delim == Delimiter::Hash {
+            self.push(TokenKind::Comment, start, end);
+            return;
+        }
+
+        let content = &self.bytes()[start..end];
+        let expr_start = end - content.trim_ascii_start().len();
+        let expr_end = start + content.trim_ascii_end().len().max(expr_start - start);
+
+        self.push(TokenKind::Whitespace, start, expr_start);
+        match is_raw {
+            true => self.push(TokenKind::RawOpen, expr_start, expr_end),
+            false => self.push(TokenKind::Expr, expr_start, expr_end),
+        }
+        self.push(TokenKind::Whitespace, expr_end, end);
+    }
+
+    fn lex_raw(&mut self) {
+        let source = self.bytes();
+        let start = self.index;
+        let mut current = start;
+
+        loop {
+            current = self.open.find(source, current).unwrap_or(source.len());
+
+            if self.suspend(current) {
+                return;
+            }
+
+            if current == source.len() {
+                // unclosed raw block
+                self.push(TokenKind::Raw, start, current);
+                self.index = current;
+                self.state = LexState::Eof;
+                return;
+            }
+
+            if let Some(trim) = self.match_endraw(&source[current..]) {
+                match trim {
+                    true => _ = self.push_trim_end(TokenKind::Raw, start, current),
+                    false => self.push(TokenKind::Raw, start, current),
+                }
+                // the `endraw` tag is lexed as regular expression
+                self.index = current;
+                self.state = LexState::Static;
+                return;
+            }
+
+            current += 1;
+        }
+    }
+
+    /// Returns the index of the first `{{ endraw }}` starting from `current`.
+    fn find_endraw(&self, mut current: usize) -> Option<usize> {
+        let source = self.bytes();
+        loop {
+            current = self.open.find(source, current)?;
+            if self.match_endraw(&source[current..]).is_some() {
+                return Some(current);
+            }
+            current += 1;
+        }
+    }
+
+    /// Match `{{ endraw }}` in any delimiter with optional whitespace control.
+    ///
+    /// Returns whether whitespace before the tag should be trimmed.
+    fn match_endraw(&self, source: &[u8]) -> Option<bool> {
+        let (open, len) = self.syntax.match_open(source)?;
+
+        let (trim, rest) = match &source[len..] {
+            [TRIM, rest @ ..] => (true, rest),
+            rest => (false, rest),
+        };
+        let rest = rest.trim_ascii_start().strip_prefix(ENDRAW.as_bytes())?;
+        let rest = rest.trim_ascii_start();
+        let rest = rest.strip_prefix(&[TRIM]).unwrap_or(rest);
+
+        match self.syntax.match_close(rest, open)? {
+            (close, _) if close == open => Some(trim),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            if self.suspended || matches!(self.state, LexState::Eof) {
+                return None;
+            }
+            self.lex();
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Vectorized search of bytes that may start a delimiter.
+#[derive(Clone, Copy)]
+enum Needle {
+    Empty,
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+    /// too many bytes, every position is a candidate
+    Any,
+}
+
+impl Needle {
+    /// Opening delimiter, or escape before it.
+    fn open(syntax: &Syntax) -> Self {
+        let mut bytes = Delimiter::ALL
+            .into_iter()
+            .filter_map(|delim| Some(syntax.get(delim)?.0.as_bytes()[0]))
+            .peekable();
+        match bytes.peek() {
+            Some(_) => Self::new(bytes.chain([ESCAPE])),
+            None => Self::Empty,
+        }
+    }
+
+    /// Closing delimiter.
+    fn close(syntax: &Syntax) -> Self {
+        Self::new(
+            Delimiter::ALL
+                .into_iter()
+                .filter_map(|delim| Some(syntax.get(delim)?.1.as_bytes()[0])),
+        )
+    }
+
+    fn new(bytes: impl IntoIterator<Item = u8>) -> Self {
+        let mut set = [0u8; 3];
+        let mut len = 0;
+        for byte in bytes {
+            if set[..len].contains(&byte) {
+                continue;
+            }
+            if len == set.len() {
+                return Self::Any;
+            }
+            set[len] = byte;
+            len += 1;
+        }
+
+        match (len, set) {
+            (0, _) => Self::Empty,
+            (1, [a, ..]) => Self::One(a),
+            (2, [a, b, ..]) => Self::Two(a, b),
+            (_, [a, b, c]) => Self::Three(a, b, c),
+        }
+    }
+
+    /// Returns the position of the next candidate starting from `start`.
+    fn find(self, source: &[u8], start: usize) -> Option<usize> {
+        let haystack = &source[start..];
+        let found = match self {
+            Self::Empty => None,
+            Self::One(a) => memchr::memchr(a, haystack),
+            Self::Two(a, b) => memchr::memchr2(a, b, haystack),
+            Self::Three(a, b, c) => memchr::memchr3(a, b, c, haystack),
+            Self::Any => (!haystack.is_empty()).then_some(0),
+        };
+        found.map(|i| start + i)
+    }
+}
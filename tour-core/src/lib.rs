@@ -21,6 +21,11 @@
 //! There is also [`StaticVisitor`] that only collect static content. This implementations is used
 //! in runtime template reloading.
 //!
+//! [`StreamParser`] parse template fed by chunks, e.g. from a file, without loading it whole.
+//!
+//! [`Parser`] is built on top of [`Lexer`], a lossless tokenizer which can be used directly for
+//! tooling like editor support or formatter.
+//!
 //! # Example
 //!
 //! ```
@@ -33,15 +38,91 @@
 //! assert_eq!(&visitor.statics[..], &["Hello "," !"]);
 //! ```
 //!
+//! # Comment
+//!
+//! Content between `{#` and `#}` is discarded entirely, including any delimiter inside it, and
+//! never reaches the [`Visitor`].
+//!
+//! ```
+//! use tour_core::{Parser, StaticVisitor};
+//!
+//! let source = "Hello {# {{ for user in }} #}World";
+//!
+//! let visitor = Parser::new(source, StaticVisitor::new()).parse().unwrap();
+//!
+//! assert_eq!(&visitor.statics[..], &["Hello ","World"]);
+//! ```
+//!
+//! # Raw
+//!
+//! Content between `{{ raw }}` and `{{ endraw }}` is not interpreted, and is passed as a single
+//! static content. Without the matching `{{ endraw }}`, `{{ raw }}` is a regular expression.
+//!
+//! A single opening delimiter in static content can be escaped using backslash, e.g. `\{{` will
+//! be rendered as `{{`. The backslash itself can be escaped, e.g. `\\{{ name }}` will be
+//! rendered as `\` followed by the expression.
+//!
+//! ```
+//! use tour_core::{Parser, StaticVisitor};
+//!
+//! let source = r"<p>\{{ name }}</p>{{ raw }}<p>{{ name }}</p>{{ endraw }}";
+//!
+//! let visitor = Parser::new(source, StaticVisitor::new()).parse().unwrap();
+//!
+//! assert_eq!(&visitor.statics[..], &["<p>","{{ name }}</p>","<p>{{ name }}</p>"]);
+//!
+//! let visitor = Parser::new("<p>{{ raw }}</p>", StaticVisitor::new()).parse().unwrap();
+//!
+//! assert_eq!(&visitor.statics[..], &["<p>","</p>"]);
+//!
+//! let visitor = Parser::new(r"C:\\{{ dir }}", StaticVisitor::new()).parse().unwrap();
+//!
+//! assert_eq!(&visitor.statics[..], &[r"C:\"]);
+//! ```
+//!
+//! # Whitespace control
+//!
+//! Adding `-` right after the opening delimiter or right before the closing delimiter, e.g.
+//! `{{- expr -}}`, will trim whitespaces of the static content before or after the expression
+//! respectively. This applies to all [`Delimiter`] kind.
+//!
+//! ```
+//! use tour_core::{Parser, StaticVisitor};
+//!
+//! let source = "<ul>\n  {{- for user in users -}}\n  <li>{{ user }}</li>\n  {{- endfor }}\n</ul>";
+//!
+//! let visitor = Parser::new(source, StaticVisitor::new()).parse().unwrap();
+//!
+//! assert_eq!(&visitor.statics[..], &["<ul>","<li>","</li>","\n</ul>"]);
+//! ```
+//!
+//! # Features
+//!
+//! - `std` (default): `std::io::Read` support in [`StreamParser`]. Without it, this crate is
+//!   `no_std` and only requires `alloc`.
+//!
 //! [1]: <https://docs.rs/tour>
 //! [2]: <https://docs.rs/tour-macros>
 //! [3]: <https://docs.rs/syn>
+#![no_std]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 mod syntax;
+mod span;
 mod visitor;
+mod lexer;
 mod parser;
+mod stream;
 mod error;
 
-pub use syntax::Delimiter;
-pub use visitor::{Visitor, StaticVisitor};
+pub use syntax::{Delimiter, Syntax};
+pub use span::Span;
+pub use visitor::{Visitor, StaticVisitor, OwnedStaticVisitor};
+pub use lexer::{Lexer, Token, TokenKind};
 pub use parser::Parser;
+pub use stream::StreamParser;
 pub use error::{Result, ParseError};
+#[cfg(feature = "std")]
+pub use error::ReadError;
@@ -1,14 +1,16 @@
-use crate::{Delimiter, ParseError, Result, visitor::Visitor};
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    Delimiter, Lexer, ParseError, Result, Span, Syntax, Token, TokenKind,
+    lexer::ENDRAW,
+    visitor::Visitor,
+};
 
 /// Template source code parser.
 ///
 /// For more details see the [crate level docs][crate].
 pub struct Parser<'a,V> {
-    source: &'a [u8],
-
-    // parser states
-    index: usize,
-    state: ParseState,
+    lexer: Lexer<'a>,
     visitor: V,
 }
 
@@ -20,19 +22,16 @@ impl<'a, V> Parser<'a, V> {
     /// For static content only, use [`StaticVisitor`][super::StaticVisitor].
     pub fn new(source: &'a str, visitor: V) -> Self {
         Self {
-            source: source.as_bytes(),
-            index: 0,
-            state: ParseState::Static { start: 0 },
+            lexer: Lexer::new(source),
             visitor,
         }
     }
-}
 
-enum ParseState {
-    Static { start: usize },
-    Expr { start: usize, open_delim: Delimiter },
-    OpenExpr { start: usize, brace: usize, },
-    CloseExpr { start: usize, brace: usize, open_delim: Delimiter, close_delim: Delimiter, },
+    /// Use given delimiter [`Syntax`].
+    pub fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.lexer = self.lexer.with_syntax(syntax);
+        self
+    }
 }
 
 impl<'a,V> Parser<'a,V>
@@ -40,84 +39,158 @@ where
     V: Visitor<'a>,
 {
     /// Start parsing.
-    pub fn parse(mut self) -> Result<V> {
-        loop {
-            let current = self.index;
-            let Some(byte) = self.source.get(current) else {
-                break self.parse_leftover()?;
-            };
-
-            match self.state {
-                ParseState::Static { start } => {
-                    self.index += 1;
-                    if matches!(byte,b'{') {
-                        self.state = ParseState::OpenExpr { start, brace: current }
-                    }
+    ///
+    /// Parsing stops at the first error.
+    pub fn parse(self) -> Result<V> {
+        self.parse_inner(false).map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Start parsing, continue on error and collect all of them.
+    ///
+    /// [`Visitor`] is expected to be able to continue after returning an error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tour_core::{Parser, StaticVisitor};
+    ///
+    /// let source = "{{ name !} {{ age %} {{ city";
+    ///
+    /// let Err(errors) = Parser::new(source, StaticVisitor::new()).parse_all() else {
+    ///     unreachable!()
+    /// };
+    ///
+    /// assert_eq!(errors.len(), 3);
+    /// ```
+    pub fn parse_all(self) -> Result<V, Vec<ParseError>> {
+        self.parse_inner(true)
+    }
+
+    fn parse_inner(mut self, recover: bool) -> Result<V, Vec<ParseError>> {
+        let len = self.lexer.source().len();
+        let mut state = ParseState::new(recover);
+        state.visit(self.lexer, &mut self.visitor);
+        state.finish(len, self.visitor)
+    }
+}
+
+/// Parsing state of a template, shared with [`StreamParser`][crate::StreamParser].
+pub(crate) struct ParseState {
+    recover: bool,
+    errors: Vec<ParseError>,
+    /// currently open expression
+    open: Option<(Delimiter, Span)>,
+    /// currently in raw block
+    raw: bool,
+}
+
+impl ParseState {
+    pub(crate) fn new(recover: bool) -> Self {
+        Self { recover, errors: vec![], open: None, raw: false }
+    }
+
+    /// Returns `true` if parsing should stop.
+    pub(crate) fn is_stopped(&self) -> bool {
+        !self.recover && !self.errors.is_empty()
+    }
+
+    /// Take the first error.
+    pub(crate) fn take_error(&mut self) -> Option<ParseError> {
+        (!self.errors.is_empty()).then(|| self.errors.remove(0))
+    }
+
+    /// Visit tokens until exhausted, or until the first error if not recovering.
+    ///
+    /// An expression token must be visited in the same call with its closing delimiter.
+    pub(crate) fn visit<'a, V>(&mut self, tokens: impl IntoIterator<Item = Token<'a>>, visitor: &mut V)
+    where
+        V: Visitor<'a>,
+    {
+        macro_rules! report {
+            ($err:expr) => {{
+                self.errors.push($err);
+                if !self.recover {
+                    return;
                 }
-                ParseState::Expr { start, open_delim } => {
-                    self.index += 1;
-                    if let Some(close_delim) = Delimiter::match_close(*byte) {
-                        self.state = ParseState::CloseExpr {
-                            start, brace: current, open_delim, close_delim,
-                        }
+            }};
+        }
+
+        // content of currently open expression
+        let mut expr = None;
+        // currently open expression is `raw` keyword
+        let mut raw_open = false;
+
+        for token in tokens {
+            match token.kind {
+                TokenKind::Static | TokenKind::Raw => {
+                    if let Err(err) = visitor.visit_static(token.text, token.span) {
+                        report!(err.with_span(token.span));
                     }
                 }
-                ParseState::OpenExpr { start, brace } => {
-                    match Delimiter::match_open(*byte) {
-                        Some(open_delim) => {
-                            self.index += 1;
-                            self.state = ParseState::Expr { start: current + 1, open_delim };
-                            let statics = Self::parse_str(&self.source[start..brace]);
-                            if !statics.is_empty() {
-                                self.visitor.visit_static(statics)?;
-                            }
-                        }
-                        None => self.state = ParseState::Static { start }
-                    }
+                TokenKind::Open(delim) => {
+                    self.open = Some((delim, token.span));
+                    expr = None;
+                    raw_open = false;
                 }
-                ParseState::CloseExpr { start, brace, open_delim, close_delim } => {
-                    match byte {
-                        b'}' => {
-                            if open_delim != close_delim {
-                                return Err(ParseError::Generic(format!(
-                                    "delimiter shold be same, open `{}` closed with `{}`",
-                                    open_delim,close_delim,
-                                )));
-                            }
-
-                            self.index += 1;
-                            self.state = ParseState::Static { start: current + 1 };
-                            self.visitor.visit_expr(Self::parse_str(&self.source[start..brace]), open_delim)?;
-                        }
-                        _ => self.state = ParseState::Expr { start, open_delim }
+                TokenKind::Expr => expr = Some((token.text, token.span)),
+                TokenKind::RawOpen => raw_open = true,
+                TokenKind::Close(close) => {
+                    let (delim, span) = self.open.take().expect("lexer emit `Close` after `Open`");
+                    let (source, expr_span) = expr.take().unwrap_or(("", span));
+
+                    if delim != close {
+                        report!(ParseError::Mismatch {
+                            open: delim,
+                            close,
+                            span: Span { end: token.span.end, ..span },
+                        });
+                        continue;
+                    }
+
+                    match delim {
+                        Delimiter::Hash => {}
+                        // lexer only emit `RawOpen` when the matching `endraw` follows
+                        _ if raw_open => self.raw = true,
+                        _ if self.raw && source == ENDRAW => self.raw = false,
+                        _ => if let Err(err) = visitor.visit_expr(source, delim, expr_span) {
+                            report!(err.with_span(expr_span));
+                        },
                     }
                 }
+                TokenKind::Trimmed
+                | TokenKind::Escape
+                | TokenKind::Trim
+                | TokenKind::Whitespace
+                | TokenKind::Comment => {}
             }
         }
-
-        self.visitor.finish()
     }
 
-    fn parse_leftover(&mut self) -> Result<()> {
-        match self.state {
-            ParseState::Static { start } | ParseState::OpenExpr { start, .. } => {
-                let statics = Self::parse_str(&self.source[start..]);
-                if statics.is_empty() {
-                    Ok(())
-                } else {
-                    self.visitor.visit_static(statics)
-                }
-            },
-            ParseState::Expr { .. } | ParseState::CloseExpr { .. } => {
-                // we dont have the closing delimiter here, just bail out
-                Err(ParseError::Generic("unclosed expression".to_owned()))
-            },
+    /// Finish parsing, `len` is the length of the whole template.
+    pub(crate) fn finish<'a, V>(mut self, len: usize, visitor: V) -> Result<V, Vec<ParseError>>
+    where
+        V: Visitor<'a>,
+    {
+        if self.is_stopped() {
+            return Err(self.errors);
+        }
+
+        if let Some((delim, span)) = self.open {
+            // we dont have the closing delimiter here
+            self.errors.push(ParseError::Unclosed { delim, span: Span { end: len, ..span } });
         }
-    }
 
-    fn parse_str(source: &[u8]) -> &str {
-        std::str::from_utf8(source)
-            .expect("the input is string and we only check using byte char")
+        if self.is_stopped() {
+            return Err(self.errors);
+        }
+
+        match visitor.finish() {
+            Ok(visitor) if self.errors.is_empty() => Ok(visitor),
+            Ok(_) => Err(self.errors),
+            Err(err) => {
+                self.errors.push(err);
+                Err(self.errors)
+            }
+        }
     }
 }
-
@@ -0,0 +1,72 @@
+/// A region of template source.
+///
+/// `start` and `end` are byte offsets, `line` and `col` point to the `start` position.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Span {
+    /// Byte offset of the start of the region.
+    pub start: usize,
+    /// Byte offset of the end of the region, exclusive.
+    pub end: usize,
+    /// Line number of the start of the region, starting from 1.
+    pub line: u32,
+    /// Column of the start of the region in characters, starting from 1.
+    pub col: u32,
+}
+
+impl Span {
+    /// Returns the byte range of the region.
+    pub fn range(&self) -> core::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl core::fmt::Display for Span {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// Incremental line and column calculation.
+///
+/// Offsets are expected to be requested in increasing order, which is the order the
+/// [`Parser`][crate::Parser] emits chunks.
+pub(crate) struct Location {
+    offset: usize,
+    line: u32,
+    col: u32,
+    /// location of the start of the source
+    start: (u32, u32),
+}
+
+impl Location {
+    /// Start counting from given line and column.
+    pub(crate) fn resume(line: u32, col: u32) -> Self {
+        Self { offset: 0, line, col, start: (line, col) }
+    }
+
+    /// Create [`Span`] of `start..end` in `source`.
+    pub(crate) fn span(&mut self, source: &[u8], start: usize, end: usize) -> Span {
+        if start < self.offset {
+            *self = Self::resume(self.start.0, self.start.1);
+        }
+
+        let skipped = &source[self.offset..start];
+        match memchr::memrchr(b'\n', skipped) {
+            Some(last) => {
+                self.line += memchr::memchr_iter(b'\n', skipped).count() as u32;
+                self.col = 1 + chars(&skipped[last + 1..]);
+            }
+            None => self.col += chars(skipped),
+        }
+
+        self.offset = start;
+
+        Span { start, end, line: self.line, col: self.col }
+    }
+}
+
+/// Count characters in utf8 bytes.
+fn chars(bytes: &[u8]) -> u32 {
+    // utf8 continuation byte is not a character
+    bytes.iter().filter(|&&byte| byte & 0xC0 != 0x80).count() as u32
+}
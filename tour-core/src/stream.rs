@@ -0,0 +1,183 @@
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "std")]
+use crate::ReadError;
+use crate::{
+    Lexer, ParseError, Result, Syntax,
+    lexer::Checkpoint,
+    parser::ParseState,
+    visitor::Visitor,
+};
+
+/// Template parser which is fed by chunks of input.
+///
+/// Only the unfinished part of the template is buffered, e.g. static content which its end is
+/// not yet found, so large template can be parsed without loading it whole. Delimiter may span
+/// multiple chunks, the [`Visitor`] receive the same input as parsing the whole template with
+/// [`Parser`][crate::Parser].
+///
+/// Because the input is not kept, [`Visitor`] should not borrow it.
+///
+/// # Example
+///
+/// ```
+/// use tour_core::{OwnedStaticVisitor, StreamParser};
+///
+/// let parser = StreamParser::new(OwnedStaticVisitor::new());
+/// let parser = parser.feed(b"Hello {").unwrap();
+/// let parser = parser.feed(b"{ name }} !").unwrap();
+///
+/// let visitor = parser.finish().unwrap();
+///
+/// assert_eq!(&visitor.statics[..], &["Hello "," !"]);
+/// ```
+pub struct StreamParser<V> {
+    visitor: V,
+    syntax: Syntax,
+    state: ParseState,
+    checkpoint: Checkpoint,
+    /// unfinished part of the template
+    buffer: String,
+    /// incomplete utf8 sequence at the end of the previous chunk
+    incomplete: Vec<u8>,
+}
+
+impl<V> StreamParser<V> {
+    /// Create new [`StreamParser`].
+    pub fn new(visitor: V) -> Self {
+        Self {
+            visitor,
+            syntax: Syntax::new(),
+            state: ParseState::new(false),
+            checkpoint: Checkpoint::new(),
+            buffer: String::new(),
+            incomplete: vec![],
+        }
+    }
+
+    /// Use given delimiter [`Syntax`].
+    pub fn with_syntax(mut self, syntax: Syntax) -> Self {
+        self.syntax = syntax;
+        self
+    }
+}
+
+impl<V> StreamParser<V>
+where
+    V: for<'s> Visitor<'s>,
+{
+    /// Parse the next chunk of input.
+    ///
+    /// Chunk may end in the middle of a delimiter or an utf8 character.
+    ///
+    /// Parsing stops at the first error.
+    pub fn feed(mut self, chunk: &[u8]) -> Result<Self> {
+        let mut bytes = core::mem::take(&mut self.incomplete);
+        let chunk = match bytes.is_empty() {
+            true => chunk,
+            false => {
+                bytes.extend_from_slice(chunk);
+                &bytes[..]
+            }
+        };
+
+        let valid = match core::str::from_utf8(chunk) {
+            Ok(ok) => ok,
+            Err(err) if err.error_len().is_none() => {
+                let (valid, rest) = chunk.split_at(err.valid_up_to());
+                self.incomplete.extend_from_slice(rest);
+                core::str::from_utf8(valid).expect("checked")
+            }
+            Err(err) => {
+                let offset = self.checkpoint.offset() + self.buffer.len() + err.valid_up_to();
+                return Err(ParseError::Generic(format!("invalid utf-8 at byte {offset}")));
+            }
+        };
+
+        self.buffer.push_str(valid);
+        self.lex(true)?;
+        Ok(self)
+    }
+
+    /// Parse all input from [`io::Read`].
+    ///
+    /// Input is read in chunks, a delimiter or an utf8 character may be split between reads.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use tour_core::{OwnedStaticVisitor, ReadError, StreamParser};
+    ///
+    /// // reads one byte at a time
+    /// struct Bytes<'a>(&'a [u8]);
+    ///
+    /// impl std::io::Read for Bytes<'_> {
+    ///     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    ///         let len = self.0.len().min(buf.len()).min(1);
+    ///         buf[..len].copy_from_slice(&self.0[..len]);
+    ///         self.0 = &self.0[len..];
+    ///         Ok(len)
+    ///     }
+    /// }
+    ///
+    /// let source = "Hallå {{ name }} {{ if admin }}ø{{ endif }}!";
+    /// let visitor = StreamParser::new(OwnedStaticVisitor::new())
+    ///     .read(Bytes(source.as_bytes()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(&visitor.statics[..], &["Hallå "," ","ø","!"]);
+    ///
+    /// let result = StreamParser::new(OwnedStaticVisitor::new())
+    ///     .read(Bytes(b"Hello {{ name }"));
+    ///
+    /// assert!(matches!(result, Err(ReadError::Parse(_))));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read(mut self, mut reader: impl io::Read) -> core::result::Result<V, ReadError> {
+        let mut chunk = [0u8; 8 * 1024];
+
+        loop {
+            let len = match reader.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self = self.feed(&chunk[..len])?;
+        }
+
+        Ok(self.finish()?)
+    }
+
+    /// Parse the rest of input and finish parsing.
+    pub fn finish(mut self) -> Result<V> {
+        if !self.incomplete.is_empty() {
+            let offset = self.checkpoint.offset() + self.buffer.len();
+            return Err(ParseError::Generic(format!("invalid utf-8 at byte {offset}")));
+        }
+
+        self.lex(false)?;
+
+        let len = self.checkpoint.offset() + self.buffer.len();
+        self.state.finish(len, self.visitor).map_err(|mut errors| errors.remove(0))
+    }
+
+    fn lex(&mut self, partial: bool) -> Result<()> {
+        let mut lexer = Lexer::resume(&self.buffer, self.syntax.clone(), self.checkpoint, partial);
+
+        self.state.visit(&mut lexer, &mut self.visitor);
+        if let Some(err) = self.state.take_error() {
+            return Err(err);
+        }
+
+        if partial {
+            let (len, checkpoint) = lexer.checkpoint();
+            self.checkpoint = checkpoint;
+            self.buffer.drain(..len);
+        }
+
+        Ok(())
+    }
+}
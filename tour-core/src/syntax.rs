@@ -1,8 +1,10 @@
+use alloc::borrow::Cow;
+
 
 /// An expression delimiter.
 //
 // Opening and closing delimiter must be equal.
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Delimiter {
     /// `{{ }}` escaped render.
     Brace,
@@ -12,7 +14,7 @@ pub enum Delimiter {
     Percent,
     /// `{? ?}` escaped render using `std::fmt::Debug`.
     Quest,
-    /// `{# #}` unused, same as [`Delimiter::Brace`].
+    /// `{# #}` comment, discarded by [`Parser`][crate::Parser].
     Hash,
     // /// `{@ @}`
     // At,
@@ -31,7 +33,14 @@ pub enum Delimiter {
 }
 
 impl Delimiter {
-    /// Returns [`Some`] if given byte considered as opening delimiter.
+    /// All delimiter kind.
+    pub const ALL: [Self; 5] = [Self::Brace, Self::Bang, Self::Percent, Self::Quest, Self::Hash];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns [`Some`] if given byte considered as opening delimiter in the default [`Syntax`].
     pub fn match_open(ch: u8) -> Option<Self> {
         match ch {
             b'{' => Some(Self::Brace),
@@ -43,7 +52,7 @@ impl Delimiter {
         }
     }
 
-    /// Returns [`Some`] if given byte considered as closing delimiter.
+    /// Returns [`Some`] if given byte considered as closing delimiter in the default [`Syntax`].
     pub fn match_close(ch: u8) -> Option<Self> {
         match ch {
             b'}' => Some(Self::Brace),
@@ -56,8 +65,8 @@ impl Delimiter {
     }
 }
 
-impl std::fmt::Display for Delimiter {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             Self::Brace => f.write_str("brace"),
             Self::Bang => f.write_str("!"),
@@ -68,3 +77,130 @@ impl std::fmt::Display for Delimiter {
     }
 }
 
+
+/// Delimiter syntax used by [`Parser`][crate::Parser].
+///
+/// By default, all [`Delimiter`] are in the form of `{x x}`. Each delimiter can be changed to
+/// another pair of opening and closing string, or disabled entirely.
+///
+/// # Example
+///
+/// ```
+/// use tour_core::{Delimiter, Parser, StaticVisitor, Syntax};
+///
+/// let syntax = Syntax::new()
+///     .with(Delimiter::Brace, "<%", "%>")
+///     .with(Delimiter::Hash, "<%#", "%>")
+///     .without(Delimiter::Percent);
+///
+/// let source = r"\frac{%}{2} <%# note %><% name %> {{ name }}";
+///
+/// let visitor = Parser::new(source, StaticVisitor::new())
+///     .with_syntax(syntax)
+///     .parse()
+///     .unwrap();
+///
+/// assert_eq!(&visitor.statics[..], &[r"\frac{%}{2} "," {{ name }}"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Syntax {
+    delims: [Option<(Cow<'static, str>, Cow<'static, str>)>; 5],
+}
+
+impl Syntax {
+    /// Create the default [`Syntax`].
+    pub const fn new() -> Self {
+        Self {
+            delims: [
+                Some((Cow::Borrowed("{{"), Cow::Borrowed("}}"))),
+                Some((Cow::Borrowed("{!"), Cow::Borrowed("!}"))),
+                Some((Cow::Borrowed("{%"), Cow::Borrowed("%}"))),
+                Some((Cow::Borrowed("{?"), Cow::Borrowed("?}"))),
+                Some((Cow::Borrowed("{#"), Cow::Borrowed("#}"))),
+            ],
+        }
+    }
+
+    /// Set the opening and closing string of given [`Delimiter`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if either `open` or `close` is empty.
+    pub fn with(
+        mut self,
+        delim: Delimiter,
+        open: impl Into<Cow<'static, str>>,
+        close: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        let (open, close) = (open.into(), close.into());
+        assert!(!open.is_empty() && !close.is_empty(), "delimiter cannot be empty");
+        self.delims[delim.index()] = Some((open, close));
+        self
+    }
+
+    /// Disable given [`Delimiter`].
+    pub fn without(mut self, delim: Delimiter) -> Self {
+        self.delims[delim.index()] = None;
+        self
+    }
+
+    /// Returns the opening and closing string of given [`Delimiter`], if enabled.
+    pub fn get(&self, delim: Delimiter) -> Option<(&str, &str)> {
+        self.delims[delim.index()]
+            .as_ref()
+            .map(|(open, close)| (open.as_ref(), close.as_ref()))
+    }
+
+    /// Returns the length of the longest opening or closing delimiter.
+    pub(crate) fn max_len(&self) -> usize {
+        Delimiter::ALL
+            .into_iter()
+            .filter_map(|delim| self.get(delim))
+            .map(|(open, close)| open.len().max(close.len()))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns `true` if this is the default syntax.
+    pub fn is_default(&self) -> bool {
+        self == &Self::new()
+    }
+
+    /// Returns the [`Delimiter`] and its length if `source` starts with an opening delimiter.
+    ///
+    /// If multiple delimiter matches, the longest one is returned.
+    pub fn match_open(&self, source: &[u8]) -> Option<(Delimiter, usize)> {
+        Delimiter::ALL
+            .into_iter()
+            .filter_map(|delim| {
+                let (open, _) = self.get(delim)?;
+                source.starts_with(open.as_bytes()).then_some((delim, open.len()))
+            })
+            .max_by_key(|(_, len)| *len)
+    }
+
+    /// Returns the [`Delimiter`] and its length if `source` starts with a closing delimiter.
+    ///
+    /// The closing delimiter of `open` takes precedence, otherwise the longest one is returned.
+    pub fn match_close(&self, source: &[u8], open: Delimiter) -> Option<(Delimiter, usize)> {
+        if let Some((_, close)) = self.get(open)
+            && source.starts_with(close.as_bytes())
+        {
+            return Some((open, close.len()));
+        }
+
+        Delimiter::ALL
+            .into_iter()
+            .filter_map(|delim| {
+                let (_, close) = self.get(delim)?;
+                source.starts_with(close.as_bytes()).then_some((delim, close.len()))
+            })
+            .max_by_key(|(_, len)| *len)
+    }
+}
+
+impl Default for Syntax {
+    fn default() -> Self {
+        Self::new()
+    }
+}
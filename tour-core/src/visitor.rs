@@ -1,14 +1,21 @@
-use std::borrow::Cow;
+use alloc::{borrow::Cow, string::String, vec, vec::Vec};
 
-use crate::{Delimiter, Result};
+use crate::{Delimiter, Result, Span};
 
 /// This trait represents a visitor that collect input sources through a [`Parser`][super::Parser].
+///
+/// Errors without location returned from `visit_static` or `visit_expr` will be attached with the
+/// location of the visited source.
 pub trait Visitor<'a>: Sized {
     /// Collect static content.
-    fn visit_static(&mut self, source: &'a str) -> Result<()>;
+    ///
+    /// `span` is the location of `source` in the template.
+    fn visit_static(&mut self, source: &'a str, span: Span) -> Result<()>;
 
     /// Collect expression.
-    fn visit_expr(&mut self, source: &'a str, delim: Delimiter) -> Result<()>;
+    ///
+    /// `span` is the location of `source` in the template, excluding the delimiters.
+    fn visit_expr(&mut self, source: &'a str, delim: Delimiter, span: Span) -> Result<()>;
 
     /// Final check on finish parsing.
     fn finish(self) -> Result<Self>;
@@ -40,12 +47,12 @@ impl StaticVisitor<'_> {
 }
 
 impl<'a> Visitor<'a> for StaticVisitor<'a> {
-    fn visit_static(&mut self, source: &'a str) -> Result<()> {
+    fn visit_static(&mut self, source: &'a str, _: Span) -> Result<()> {
         self.statics.push(Cow::Borrowed(source));
         Ok(())
     }
 
-    fn visit_expr(&mut self, _: &'a str, _: Delimiter) -> Result<()> {
+    fn visit_expr(&mut self, _: &'a str, _: Delimiter, _: Span) -> Result<()> {
         Ok(())
     }
 
@@ -60,3 +67,39 @@ impl Default for StaticVisitor<'_> {
     }
 }
 
+
+/// [`Visitor`] implementation that only collect static content as owned [`String`].
+///
+/// Unlike [`StaticVisitor`], this does not borrow the source, which is required by
+/// [`StreamParser`][super::StreamParser].
+pub struct OwnedStaticVisitor {
+    pub statics: Vec<String>
+}
+
+impl OwnedStaticVisitor {
+    /// Create new [`OwnedStaticVisitor`].
+    pub fn new() -> Self {
+        Self { statics: vec![] }
+    }
+}
+
+impl Visitor<'_> for OwnedStaticVisitor {
+    fn visit_static(&mut self, source: &str, _: Span) -> Result<()> {
+        self.statics.push(source.into());
+        Ok(())
+    }
+
+    fn visit_expr(&mut self, _: &str, _: Delimiter, _: Span) -> Result<()> {
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Self> {
+        Ok(self)
+    }
+}
+
+impl Default for OwnedStaticVisitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
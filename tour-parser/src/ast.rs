@@ -25,6 +25,18 @@ pub enum Scalar {
     Render(RenderTempl),
     /// Render body for layout.
     Yield(YieldTempl),
+    /// Render the overridden layout block.
+    Super {
+        templ: Token![super],
+        /// the enclosing block
+        block: Ident,
+    },
+    /// Call a macro.
+    Call(CallTempl),
+    /// Loop control flow, `break` or `continue`.
+    Control(ControlTempl),
+    /// Local binding that will be generated as is.
+    Let(Rc<LetTempl>),
     /// Rust item that will be generated as is.
     Item(Rc<ItemTempl>),
     /// Rust expression.
@@ -50,22 +62,163 @@ pub enum Scope {
         stmts: Vec<StmtTempl>,
         else_branch: Option<(Token![else],Box<Scope>)>
     },
+    /// While statement.
+    While {
+        templ: WhileTempl,
+        stmts: Vec<StmtTempl>,
+    },
+    /// Loop statement.
+    Loop {
+        templ: LoopTempl,
+        stmts: Vec<StmtTempl>,
+    },
+    /// Match statement.
+    Match {
+        templ: MatchTempl,
+        arms: Vec<(WhenTempl, Vec<StmtTempl>)>,
+    },
+    /// With statement.
+    With {
+        templ: WithTempl,
+        stmts: Vec<StmtTempl>,
+        else_branch: Option<(Token![else],Box<Scope>)>
+    },
+    /// Capture rendered content into a local.
+    Capture {
+        templ: CaptureTempl,
+        stmts: Vec<StmtTempl>,
+    },
+    /// Render external template in place, filling its slots.
+    Render {
+        templ: RenderTempl,
+        /// Content for the unnamed slot.
+        stmts: Vec<StmtTempl>,
+        /// Content for named slots.
+        slots: Vec<(SlotTempl, Vec<StmtTempl>)>,
+    },
+    /// Yield child block, with default content if the child does not declare it.
+    Yield {
+        templ: YieldTempl,
+        stmts: Vec<StmtTempl>,
+    },
+    /// Slot placeholder with its default content, or a named slot content in `render` scope.
+    Slot {
+        templ: SlotTempl,
+        stmts: Vec<StmtTempl>,
+    },
     /// Block declaration.
     Block {
         templ: BlockTempl,
         stmts: Vec<StmtTempl>,
     },
+    /// Macro declaration.
+    Macro {
+        templ: MacroTempl,
+        stmts: Vec<StmtTempl>,
+    },
+}
+
+/// Returns `true` if any statement, including the nested ones, matches the predicate.
+pub(crate) fn any_stmt(stmts: &[StmtTempl], f: &impl Fn(&StmtTempl) -> bool) -> bool {
+    stmts.iter().any(|stmt| {
+        f(stmt) || match stmt {
+            StmtTempl::Scope(scope) => scope.children().into_iter().any(|stmts| any_stmt(stmts, f)),
+            StmtTempl::Scalar(_) => false,
+        }
+    })
+}
+
+/// Retain statements matching the predicate, including the nested ones.
+pub(crate) fn retain_stmt(stmts: &mut Vec<StmtTempl>, f: &impl Fn(&StmtTempl) -> bool) {
+    stmts.retain(f);
+    for stmt in stmts {
+        if let StmtTempl::Scope(scope) = stmt {
+            for stmts in scope.children_mut() {
+                retain_stmt(stmts, f);
+            }
+        }
+    }
 }
 
 impl Scope {
+    /// Returns all nested statements, including `else` branch.
+    fn children(&self) -> Vec<&[StmtTempl]> {
+        match self {
+            Self::If { stmts, else_branch, .. }
+            | Self::For { stmts, else_branch, .. }
+            | Self::With { stmts, else_branch, .. } => {
+                let mut children = vec![&stmts[..]];
+                if let Some((_, scope)) = else_branch {
+                    children.extend(scope.children());
+                }
+                children
+            },
+            Self::Match { arms, .. } => arms.iter().map(|(_, stmts)| &stmts[..]).collect(),
+            Self::Render { stmts, slots, .. } => {
+                let mut children = vec![&stmts[..]];
+                children.extend(slots.iter().map(|(_, stmts)| &stmts[..]));
+                children
+            },
+            Self::Root { stmts }
+            | Self::While { stmts, .. }
+            | Self::Loop { stmts, .. }
+            | Self::Capture { stmts, .. }
+            | Self::Slot { stmts, .. }
+            | Self::Yield { stmts, .. }
+            | Self::Block { stmts, .. }
+            | Self::Macro { stmts, .. } => vec![stmts],
+        }
+    }
+
+    /// Mutable version of [`Scope::children`].
+    fn children_mut(&mut self) -> Vec<&mut Vec<StmtTempl>> {
+        match self {
+            Self::If { stmts, else_branch, .. }
+            | Self::For { stmts, else_branch, .. }
+            | Self::With { stmts, else_branch, .. } => {
+                let mut children = vec![stmts];
+                if let Some((_, scope)) = else_branch {
+                    children.extend(scope.children_mut());
+                }
+                children
+            },
+            Self::Match { arms, .. } => arms.iter_mut().map(|(_, stmts)| stmts).collect(),
+            Self::Render { stmts, slots, .. } => {
+                let mut children = vec![stmts];
+                children.extend(slots.iter_mut().map(|(_, stmts)| stmts));
+                children
+            },
+            Self::Root { stmts }
+            | Self::While { stmts, .. }
+            | Self::Loop { stmts, .. }
+            | Self::Capture { stmts, .. }
+            | Self::Slot { stmts, .. }
+            | Self::Yield { stmts, .. }
+            | Self::Block { stmts, .. }
+            | Self::Macro { stmts, .. } => vec![stmts],
+        }
+    }
+
     pub(crate) fn stack_mut(&mut self) -> &mut Vec<StmtTempl> {
         match self {
             Self::Root { stmts } => stmts,
             Self::Block { stmts, .. } => stmts,
+            Self::Macro { stmts, .. } => stmts,
             Self::For { else_branch: Some(branch), .. } => branch.1.stack_mut(),
             Self::For { stmts, .. } => stmts,
             Self::If { else_branch: Some(branch), .. } => branch.1.stack_mut(),
             Self::If { stmts, .. } => stmts,
+            Self::While { stmts, .. } => stmts,
+            Self::Capture { stmts, .. } => stmts,
+            Self::Render { stmts, .. } => stmts,
+            Self::Slot { stmts, .. } => stmts,
+            Self::Yield { stmts, .. } => stmts,
+            Self::With { else_branch: Some(branch), .. } => branch.1.stack_mut(),
+            Self::With { stmts, .. } => stmts,
+            Self::Loop { stmts, .. } => stmts,
+            Self::Match { arms, .. } => {
+                &mut arms.last_mut().expect("content before `when` is rejected").1
+            },
         }
     }
 }
@@ -16,14 +16,22 @@ mod sizehint;
 
 /// Generate code from [`DeriveInput`].
 pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
-    let conf = Config::default();
+    let conf = Config::load()?;
     let meta = Metadata::from_attrs(&input.attrs, &conf)?;
     let file = File::from_meta(&meta)?;
     let templ = Template::new(input.ident.clone(), meta, file)?;
     let mut root = quote! { const _: () = };
 
     brace(&mut root, |tokens| {
-        generate_templ(&templ, input, tokens);
+        // trigger recompile when configuration changed
+        if let Some(file) = conf.file() {
+            tokens.extend(quote! { const _: &str = include_str!(#file); });
+        }
+        for item in conf.prelude() {
+            tokens.extend(quote! { #[allow(unused)] #item });
+        }
+        generate_items(&templ, &mut vec![], tokens);
+        generate_templ(&templ, input, input, &[], tokens);
     });
 
     <Token![;]>::default().to_tokens(&mut root);
@@ -31,7 +39,41 @@ pub fn derive(input: &DeriveInput) -> Result<TokenStream> {
     Ok(root)
 }
 
-fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream) {
+/// Block overridden by a template extending the layout.
+struct Override {
+    name: Ident,
+    /// number of `.0` from the layout to reach the overriding template
+    depth: usize,
+}
+
+/// Emit toplevel items once, shared by every generated body.
+///
+/// Templates loaded more than once, e.g. imported by both a child and its layout, only emit
+/// their items the first time.
+fn generate_items<'a>(templ: &'a Template, done: &mut Vec<&'a str>, root: &mut TokenStream) {
+    let path = templ.meta().path();
+    if done.contains(&path) {
+        return;
+    }
+    done.push(path);
+
+    // not every body uses every item
+    for item in templ.file().items() {
+        root.extend(quote! { #[allow(unused)] #item });
+    }
+
+    for import in templ.file().imports().iter().chain(templ.file().partials()) {
+        generate_items(import.templ(), done, root);
+    }
+}
+
+fn generate_templ(
+    templ: &Template,
+    input: &DeriveInput,
+    main: &DeriveInput,
+    overrides: &[Override],
+    root: &mut TokenStream,
+) {
     let ident = &input.ident;
     let (g1, g2, g3) = input.generics.split_for_impl();
 
@@ -62,7 +104,7 @@ fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream)
         });
 
         brace(trait_tokens, |render_into| {
-            body::Visitor::generate(templ, input, render_into);
+            body::Visitor::generate(templ, input, main, overrides, render_into);
         });
 
         // ===== render_block_into() =====
@@ -80,7 +122,7 @@ fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream)
                     let name_str = name.to_string();
                     tokens.extend(quote! { #name_str => });
                     brace(tokens, |tokens| {
-                        body::Visitor::generate_block(templ, name, input, tokens);
+                        body::Visitor::generate_block(templ, name, input, main, overrides, tokens);
                     });
                 }
                 tokens.extend(quote! { _ => Err(::tour::Error::NoBlock), });
@@ -163,6 +205,36 @@ fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream)
         });
     });
 
+    // ===== macros =====
+
+    let macros = templ.file().macros();
+    if !macros.is_empty() {
+        root.extend(quote! {
+            #[automatically_derived]
+            impl #g1 #ident #g2 #g3
+        });
+
+        brace(root, |tokens| {
+            for mac in macros {
+                let name = body::macro_fn(&mac.templ.name);
+                let inputs = mac.templ.inputs.iter().enumerate().map(|(i, arg)| {
+                    let ident = body::macro_arg(i);
+                    let ty = &arg.ty;
+                    quote! { #ident: #ty }
+                });
+                tokens.extend(quote! {
+                    #[doc(hidden)]
+                    // fields are destructured but not every macro uses them
+                    #[allow(dead_code, unused_variables)]
+                    fn #name(&self, writer: &mut impl #TemplWrite, #(#inputs),*) -> ::tour::Result<()>
+                });
+                brace(tokens, |tokens| {
+                    body::Visitor::generate_macro(templ, mac, input, main, overrides, tokens);
+                });
+            }
+        });
+    }
+
     // ===== trait TemplDisplay =====
 
     if matches!(templ.meta().kind(), TemplKind::Main) {
@@ -180,12 +252,7 @@ fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream)
 
     for import in templ.file().imports() {
         let name = import.alias();
-        let path = import
-            .templ()
-            .meta()
-            .path()
-            .trim_start_matches(path::cwd().to_str().unwrap_or(""))
-            .trim_start_matches("/");
+        let path = path::display(import.templ().meta().path());
         let doc = if path.is_empty() {
             quote! { }
         } else {
@@ -204,7 +271,21 @@ fn generate_templ(templ: &Template, input: &DeriveInput, root: &mut TokenStream)
         };
         input.to_tokens(root);
 
-        generate_templ(import.templ(), &input, root);
+        // the most derived template wins
+        let is_layout = templ.file().layout_import().is_some_and(|layout| layout.path() == import.path());
+        let mut layout_overrides = vec![];
+        if is_layout {
+            for over in overrides {
+                layout_overrides.push(Override { name: over.name.clone(), depth: over.depth + 1 });
+            }
+            for block in templ.file().blocks() {
+                if !layout_overrides.iter().any(|e| e.name == block.templ.name) {
+                    layout_overrides.push(Override { name: block.templ.name.clone(), depth: 1 });
+                }
+            }
+        }
+
+        generate_templ(import.templ(), &input, main, &layout_overrides, root);
 
         root.extend(quote! {
             #[automatically_derived]
@@ -1,11 +1,18 @@
 use proc_macro2::TokenStream;
+use std::rc::Rc;
 use quote::{ToTokens, format_ident, quote};
 use syn::*;
-use tour_core::Delimiter;
+use tour_core::{Delimiter, Syntax};
 
-use crate::{ast::*, common::{TemplDisplay, INNER_BLOCK}, data::Template, file::AliasKind, syntax::*};
+use crate::{
+    ast::*,
+    common::{TemplDisplay, INNER_BLOCK, PARTIAL_SELF},
+    data::Template,
+    file::{AliasKind, MacroContent},
+    syntax::*,
+};
 
-use super::brace;
+use super::{Override, brace};
 
 pub struct Visitor<'a> {
     tokens: &'a mut TokenStream,
@@ -15,21 +22,102 @@ pub struct Visitor<'a> {
 struct Shared<'a> {
     templ: &'a Template,
     input: &'a DeriveInput,
+    /// the template value, `self` unless rendered in place
+    receiver: Ident,
+    /// static contents read when reloading
+    sources: Ident,
+    /// slot contents when rendered in place by `render` scope
+    slots: Option<Slots<'a>>,
+    /// number of nested templates rendered in place
+    depth: usize,
+    /// blocks overridden by the template extending this layout
+    overrides: &'a [Override],
+    /// the derived template
+    main: &'a DeriveInput,
+    /// resolved path of enclosing typed partials, the innermost is the last
+    partials: Vec<Rc<str>>,
+}
+
+struct Slots<'a> {
+    content: &'a [StmtTempl],
+    named: &'a [(SlotTempl, Vec<StmtTempl>)],
+    /// slot contents is rendered as part of the caller
+    caller: &'a Shared<'a>,
+}
+
+impl<'a> Shared<'a> {
+    fn new(
+        templ: &'a Template,
+        input: &'a DeriveInput,
+        main: &'a DeriveInput,
+        overrides: &'a [Override],
+    ) -> Self {
+        Self {
+            templ,
+            input,
+            receiver: Ident::new("self", proc_macro2::Span::call_site()),
+            sources: format_ident!("sources"),
+            slots: None,
+            depth: 0,
+            overrides,
+            main,
+            partials: vec![],
+        }
+    }
 }
 
 impl<'a> Visitor<'a> {
-    pub fn generate(templ: &'a Template, input: &'a DeriveInput, tokens: &'a mut TokenStream) {
+    pub fn generate(
+        templ: &'a Template,
+        input: &'a DeriveInput,
+        main: &'a DeriveInput,
+        overrides: &'a [Override],
+        tokens: &'a mut TokenStream,
+    ) {
         let mut me = Self { tokens, static_len: 0, };
-        let shared = Shared { templ, input };
+        let shared = Shared::new(templ, input, main, overrides);
         me.gens(templ.stmts(), &shared);
     }
 
-    pub fn generate_block(templ: &'a Template, block: &Ident, input: &'a DeriveInput, tokens: &'a mut TokenStream) {
+    pub fn generate_block(
+        templ: &'a Template,
+        block: &Ident,
+        input: &'a DeriveInput,
+        main: &'a DeriveInput,
+        overrides: &'a [Override],
+        tokens: &'a mut TokenStream,
+    ) {
         let mut me = Self { tokens, static_len: 0, };
-        let shared = Shared { templ, input };
+        let shared = Shared::new(templ, input, main, overrides);
         me.gens(&templ.file().block(block).stmts, &shared);
     }
 
+    pub fn generate_macro(
+        templ: &'a Template,
+        mac: &MacroContent,
+        input: &'a DeriveInput,
+        main: &'a DeriveInput,
+        overrides: &'a [Override],
+        tokens: &'a mut TokenStream,
+    ) {
+        let mut me = Self { tokens, static_len: 0, };
+        let shared = Shared::new(templ, input, main, overrides);
+        me.gen_destructure(&shared);
+
+        // arguments are bound after fields, so they shadow the fields instead
+        for (i, arg) in mac.templ.inputs.iter().enumerate() {
+            let pat = &arg.pat;
+            let ident = macro_arg(i);
+            me.tokens.extend(quote! { let #pat = #ident; });
+        }
+
+        me.gen_sources(&shared);
+        me.visit_stmts(&mac.stmts, &shared);
+        me.tokens.extend(quote! {
+            Ok(())
+        });
+    }
+
     fn gens(&mut self, stmts: &[StmtTempl], shared: &Shared) {
         self.gen_destructure(shared);
         self.gen_sources(shared);
@@ -62,25 +150,27 @@ impl<'a> Visitor<'a> {
         let meta = shared.templ.meta();
         let path = meta.path();
         let statics = shared.templ.file().statics();
+        let syntax = syntax(meta.syntax());
+        let sources = &shared.sources;
         match (meta.is_file(), meta.reload().as_bool()) {
             (true,Ok(true)) => self.tokens.extend(quote!{
-                let sources = ::std::fs::read_to_string(#path)?;
-                let sources = ::tour::Parser::new(&sources, ::tour::StaticVisitor::new())
-                    .parse()?.statics;
+                let #sources = ::tour::StreamParser::new(::tour::OwnedStaticVisitor::new())
+                    #syntax
+                    .read(::std::fs::File::open(#path)?)?.statics;
             }),
             (true,Ok(false)) | (false,Ok(false)) => {}
             (true, Err(cond)) => self.tokens.extend(quote! {
-                let sources = if #cond {
-                    let sources = ::std::fs::read_to_string(#path)?;
-                    ::tour::Parser::new(&sources, ::tour::StaticVisitor::new())
-                        .parse()?.statics
+                let #sources = if #cond {
+                    ::tour::StreamParser::new(::tour::OwnedStaticVisitor::new())
+                        #syntax
+                        .read(::std::fs::File::open(#path)?)?.statics
                 } else {
                     vec![]
                 };
             }),
             (false, _) if statics.is_empty() => {}
             (false, _) => self.tokens.extend(quote! {
-                let sources = [#(#statics),*];
+                let #sources = [#(#statics),*];
             }),
         }
     }
@@ -96,16 +186,17 @@ impl<'a> Visitor<'a> {
             StmtTempl::Scalar(scalar) => match scalar {
                 Scalar::Static { value, index } => {
                     let idx = Index::from(*index as usize);
+                    let sources = &shared.sources;
 
                     match shared.templ.meta().reload().as_bool() {
                         Ok(true) => self.tokens.extend(quote! {
-                            #TemplDisplay::display(&sources[#idx], writer)?;
+                            #TemplDisplay::display(&#sources[#idx], writer)?;
                         }),
                         Ok(false) => self.tokens.extend(quote! {
                             #TemplDisplay::display(&#value, writer)?;
                         }),
                         Err(expr) => self.tokens.extend(quote! {
-                            #TemplDisplay::display(if #expr { &sources[#idx] } else { #value }, writer)?;
+                            #TemplDisplay::display(if #expr { &#sources[#idx] } else { #value }, writer)?;
                         }),
                     }
 
@@ -113,46 +204,172 @@ impl<'a> Visitor<'a> {
                 },
                 Scalar::Yield(YieldTempl { block, .. }) => {
                     let block = block.as_ref().map(|e|e.1.to_string()).unwrap_or(INNER_BLOCK.into());
+                    let receiver = &shared.receiver;
+                    self.tokens.extend(quote! {
+                        ::tour::Template::render_block_into(#receiver.0, #block, writer)?;
+                    });
+                },
+                Scalar::Super { block, .. } => {
+                    let chain = shared.templ.layout_chain(block).expect("[BUG] validation super missed");
+                    let receiver = &shared.receiver;
+                    let layout = chain.iter().fold(quote! { #receiver }, |child, layout| {
+                        let alias = layout.alias();
+                        quote! { &#alias(#child) }
+                    });
+                    let block = block.to_string();
                     self.tokens.extend(quote! {
-                        ::tour::Template::render_block_into(self.0, #block, writer)?;
+                        ::tour::Template::render_block_into(#layout, #block, &mut *writer)?;
                     });
                 },
+                Scalar::Call(CallTempl { alias, name, args, .. }) => {
+                        let name = macro_fn(name);
+                        let args = args.iter();
+                        let receiver = &shared.receiver;
+                        let receiver = match alias {
+                            Some((alias, _)) => {
+                                let import = shared.templ.file().import_by_id(alias);
+                                let alias = import.alias();
+                                quote! { #alias(#receiver) }
+                            },
+                            None => quote! { #receiver },
+                        };
+                        self.tokens.extend(quote! {
+                            #receiver.#name(&mut *writer, #(#args),*)?;
+                        });
+                },
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), block, with: Some((_, expr)), .. })
+                    if shared.templ.file().is_recursion(path) =>
+                {
+                    let target = shared.templ.file().recursion(path).target();
+                    match shared.partials.iter().rposition(|partial| &**partial == target) {
+                        // enclosing typed partial, see `tour::partial`
+                        Some(i) => {
+                            let (partial, ctx) = match shared.partials.len() - 1 - i {
+                                0 => (quote! { __tour_rec }, quote! { __tour_ctx }),
+                                depth => {
+                                    let fields = (1..depth).map(|_| Index::from(0));
+                                    let ctx = quote! { __tour_ctx #(.#fields)* };
+                                    (quote! { #ctx.1 }, quote! { #ctx.0 })
+                                },
+                            };
+                            self.tokens.extend(quote! {
+                                #partial.render(#ctx, &(#expr), &mut *writer)?;
+                            });
+                        },
+                        // the derived template
+                        None => {
+                            let ident = &shared.main.ident;
+                            let (_, generics, _) = shared.main.generics.split_for_impl();
+                            match block {
+                                Some((_, block)) => {
+                                    let id = block.to_string();
+                                    self.tokens.extend(quote! {
+                                        <#ident #generics as ::tour::Template>::render_block_into(&(#expr), #id, &mut *writer)?;
+                                    });
+                                },
+                                None => self.tokens.extend(quote! {
+                                    <#ident #generics as ::tour::Template>::render_into(&(#expr), &mut *writer)?;
+                                }),
+                            }
+                        },
+                    }
+                },
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), block, with: Some((_, expr)), .. }) => {
+                    let templ = shared.templ.file().partial(path).templ();
+                    let stmts = match block {
+                        Some((_, block)) => &templ.file().block(block).stmts,
+                        None => templ.stmts(),
+                    };
+                    let partial = format_ident!("{PARTIAL_SELF}");
+                    let ctx = match shared.partials.is_empty() {
+                        true => quote! { () },
+                        false => quote! { (__tour_ctx, __tour_rec) },
+                    };
+
+                    let mut body = TokenStream::new();
+                    let mut inner = Shared::new(templ, shared.input, shared.main, &[]);
+                    inner.partials = shared.partials.clone();
+                    inner.partials.push(templ.meta().path().into());
+                    let mut visitor = Visitor { tokens: &mut body, static_len: self.static_len  };
+                    visitor.gen_sources(&inner);
+                    visitor.visit_stmts(stmts, &inner);
+                    self.static_len = visitor.static_len;
+
+                    // partial is rendered by a function pointer with `self` rewritten to the
+                    // value, it cannot capture anything else from the caller
+                    self.tokens.extend(quote! {
+                        ::tour::partial::render(
+                            &(#expr),
+                            &mut *writer,
+                            #ctx,
+                            |__tour_rec, __tour_ctx, #partial, writer| -> ::tour::Result<()> {
+                                #body
+                                Ok(())
+                            },
+                        )?;
+                    });
+                },
+                Scalar::Render(RenderTempl { value: RenderValue::Struct(expr), block, .. }) => {
+                    match block {
+                        Some((_, block)) => {
+                            let id = block.to_string();
+                            self.tokens.extend(quote! {
+                                ::tour::Template::render_block_into(&#expr, #id, &mut *writer)?;
+                            });
+                        },
+                        None => self.tokens.extend(quote! {
+                            ::tour::Template::render_into(&#expr, &mut *writer)?;
+                        }),
+                    }
+                },
                 Scalar::Render(RenderTempl { value: RenderValue::Ident(id), block, .. }) => {
                     match (shared.templ.file().resolve_id(id), block) {
+                        (AliasKind::Block(_), None) if let Some(over) = shared.overrides.iter().find(|e| &e.name == id) => {
+                            self.render_override(over, shared);
+                        },
                         (AliasKind::Block(block), None) => {
-                            self.visit_stmts(&block.stmts, shared)
+                            // block is scoped for its `let` bindings
+                            token::Brace::default()
+                                .surround(self.tokens, |tokens|{
+                                    let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                                    visitor.visit_stmts(&block.stmts, shared);
+                                    self.static_len = visitor.static_len;
+                                });
                         },
                         (AliasKind::Block(_), Some(_)) => unreachable!("cannot render block from block"),
                         (AliasKind::Import(import), None) => {
                             let name = import.alias();
+                            let receiver = &shared.receiver;
                             self.tokens.extend(quote! {
-                                ::tour::Template::render_into(&#name(self), &mut *writer)?;
+                                ::tour::Template::render_into(&#name(#receiver), &mut *writer)?;
                             });
                         },
                         (AliasKind::Import(import), Some((_, block))) => {
                             let name = import.alias();
+                            let receiver = &shared.receiver;
                             let id = block.to_string();
                             self.tokens.extend(quote! {
-                                ::tour::Template::render_block_into(&#name(self), #id, &mut *writer)?;
+                                ::tour::Template::render_block_into(&#name(#receiver), #id, &mut *writer)?;
                             });
                         },
                     }
                 },
                 Scalar::Render(RenderTempl { value: RenderValue::Path(path), block, .. }) => {
+                    let receiver = &shared.receiver;
                     match block {
                         Some((_, block)) => {
                             let import = shared.templ.file().import_by_path(path);
                             let name = import.alias();
                             let id = block.to_string();
                             self.tokens.extend(quote! {
-                                ::tour::Template::render_block_into(&#name(self), #id, &mut *writer)?;
+                                ::tour::Template::render_block_into(&#name(#receiver), #id, &mut *writer)?;
                             });
                         },
                         None => {
                             let import = shared.templ.file().import_by_path(path);
                             let name = import.alias();
                             self.tokens.extend(quote! {
-                                ::tour::Template::render_into(&#name(self), &mut *writer)?;
+                                ::tour::Template::render_into(&#name(#receiver), &mut *writer)?;
                             });
                         },
                     }
@@ -182,15 +399,24 @@ impl<'a> Visitor<'a> {
                     });
                 },
                 Scalar::Use(_) => unreachable!("use alias statement should be discarded"),
-                Scalar::Item(item) => match item.as_ref() {
-                    ItemTempl::Use(item) => item.to_tokens(self.tokens),
-                    ItemTempl::Const(item) => item.to_tokens(self.tokens),
-                },
+                Scalar::Control(templ) => templ.to_tokens(self.tokens),
+                Scalar::Let(templ) => templ.to_tokens(self.tokens),
+                Scalar::Item(item) => item.to_tokens(self.tokens),
             },
             StmtTempl::Scope(scope) => self.visit_scope(scope, shared),
         }
     }
 
+    /// Render block from the template extending this layout.
+    fn render_override(&mut self, over: &Override, shared: &Shared) {
+        let receiver = &shared.receiver;
+        let fields = (0..over.depth).map(|_| Index::from(0));
+        let block = over.name.to_string();
+        self.tokens.extend(quote! {
+            ::tour::Template::render_block_into(#receiver #(.#fields)*, #block, &mut *writer)?;
+        });
+    }
+
     fn visit_scope(&mut self, scope: &Scope, shared: &Shared) {
         match scope {
             Scope::Root { stmts } => {
@@ -223,13 +449,36 @@ impl<'a> Visitor<'a> {
                     let __for_expr = #expr;
                 });
 
+                // track whether the body ran, so `else` works with any iterator
+                if else_branch.is_some() {
+                    self.tokens.extend(quote! {
+                        let mut __for_empty = true;
+                    });
+                }
+
+                templ.label.to_tokens(self.tokens);
                 templ.for_token.to_tokens(self.tokens);
-                templ.pat.to_tokens(self.tokens);
-                templ.in_token.to_tokens(self.tokens);
-                format_ident!("__for_expr").to_tokens(self.tokens);
+                match &templ.with {
+                    Some((_, ident)) => {
+                        let pat = &templ.pat;
+                        self.tokens.extend(quote! {
+                            (#pat, #ident) in ::tour::Loop::iter(__for_expr)
+                        });
+                    }
+                    None => {
+                        templ.pat.to_tokens(self.tokens);
+                        templ.in_token.to_tokens(self.tokens);
+                        format_ident!("__for_expr").to_tokens(self.tokens);
+                    }
+                }
 
                 token::Brace::default()
                     .surround(self.tokens, |tokens|{
+                        if else_branch.is_some() {
+                            tokens.extend(quote! {
+                                __for_empty = false;
+                            });
+                        }
                         let mut visitor = Visitor { tokens, static_len: self.static_len  };
                         visitor.visit_stmts(stmts, shared);
                         self.static_len = visitor.static_len;
@@ -237,24 +486,227 @@ impl<'a> Visitor<'a> {
 
                 if let Some((_, else_scope)) = else_branch {
                     self.tokens.extend(quote! {
-                        if ExactSizeIterator::len(&IntoIterator::into_iter(__for_expr)) == 0
+                        if __for_empty
                     });
 
                     self.visit_scope(else_scope, shared);
                 }
             },
-            Scope::Block { .. } => unreachable!("`block` scope should be replaced with `render`")
+            Scope::While { templ, stmts } => {
+                templ.label.to_tokens(self.tokens);
+                templ.while_token.to_tokens(self.tokens);
+                templ.cond.to_tokens(self.tokens);
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.visit_stmts(stmts, shared);
+                        self.static_len = visitor.static_len;
+                    });
+            },
+            Scope::Loop { templ, stmts } => {
+                templ.label.to_tokens(self.tokens);
+                templ.loop_token.to_tokens(self.tokens);
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.visit_stmts(stmts, shared);
+                        self.static_len = visitor.static_len;
+                    });
+            },
+            Scope::Match { templ, arms } => {
+                templ.match_token.to_tokens(self.tokens);
+                templ.expr.to_tokens(self.tokens);
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        for (when, stmts) in arms {
+                            when.pat.to_tokens(tokens);
+                            if let Some((if_token, guard)) = &when.guard {
+                                if_token.to_tokens(tokens);
+                                guard.to_tokens(tokens);
+                            }
+                            <Token![=>]>::default().to_tokens(tokens);
+                            token::Brace::default()
+                                .surround(tokens, |tokens|{
+                                    let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                                    visitor.visit_stmts(stmts, shared);
+                                    self.static_len = visitor.static_len;
+                                });
+                        }
+                    });
+            },
+            Scope::With { templ, stmts, else_branch } => {
+                let WithTempl { expr, pat, .. } = templ;
+
+                // `Option` is matched by its content, see `tour::with`
+                self.tokens.extend(quote! {
+                    let __with = &(#expr);
+                    if let Some(#pat) = {
+                        use ::tour::with::{WithOption as _, WithValue as _};
+                        (&::tour::with::With(__with)).with_kind().with(__with)
+                    }
+                });
+
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.visit_stmts(stmts, shared);
+                        self.static_len = visitor.static_len;
+                    });
+
+                if let Some((else_token, else_scope)) = else_branch {
+                    else_token.to_tokens(self.tokens);
+                    self.visit_scope(else_scope, shared);
+                }
+            },
+            Scope::Capture { templ, stmts } => {
+                let name = &templ.name;
+                let capacity = super::sizehint::Visitor::new(shared.templ).capacity(stmts);
+
+                // content is already escaped when rendered
+                self.tokens.extend(quote! { let #name = });
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        tokens.extend(quote! {
+                            let mut __capture = String::with_capacity(#capacity);
+                            let writer = &mut __capture;
+                        });
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.visit_stmts(stmts, shared);
+                        self.static_len = visitor.static_len;
+                        tokens.extend(quote! {
+                            ::tour::Safe(__capture)
+                        });
+                    });
+                <Token![;]>::default().to_tokens(self.tokens);
+            },
+            Scope::Render { templ, stmts, slots } => {
+                let import = match &templ.value {
+                    RenderValue::Path(path) => shared.templ.file().import_by_path(path),
+                    RenderValue::Ident(id) => match shared.templ.file().resolve_id(id) {
+                        AliasKind::Import(import) => import,
+                        AliasKind::Block(_) => unreachable!("cannot fill slots of a block"),
+                    },
+                    RenderValue::Struct(_) => unreachable!("`render` scope requires a template"),
+                };
+                let name = import.alias();
+                let receiver = &shared.receiver;
+                let block = &templ.block;
+                let templ = import.templ();
+                let body = match block {
+                    Some((_, block)) => &templ.file().block(block).stmts,
+                    None => templ.stmts(),
+                };
+                let depth = shared.depth + 1;
+                let inner = Shared {
+                    templ,
+                    input: shared.input,
+                    receiver: format_ident!("__tour_templ{depth}"),
+                    sources: format_ident!("__tour_sources{depth}"),
+                    slots: Some(Slots { content: stmts, named: slots, caller: shared }),
+                    depth,
+                    overrides: &[],
+                    main: shared.main,
+                    partials: shared.partials.clone(),
+                };
+                let inner_receiver = &inner.receiver;
+
+                // template is rendered in place, so slot contents can borrow from the caller
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        tokens.extend(quote! {
+                            let #inner_receiver = &#name(#receiver);
+                        });
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.gen_sources(&inner);
+                        visitor.visit_stmts(body, &inner);
+                        self.static_len = visitor.static_len;
+                    });
+            },
+            Scope::Yield { templ, stmts } => {
+                let (_, block) = templ.block.as_ref().expect("`yield` scope requires a block");
+
+                // whether the child declares the block is known when rendered as a layout
+                match shared.overrides.iter().find(|e| &e.name == block) {
+                    Some(over) => self.render_override(over, shared),
+                    None => {
+                        token::Brace::default()
+                            .surround(self.tokens, |tokens|{
+                                let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                                visitor.visit_stmts(stmts, shared);
+                                self.static_len = visitor.static_len;
+                            });
+                    },
+                }
+            },
+            Scope::Slot { templ, stmts } => {
+                let fill = shared.slots.as_ref().and_then(|slots| {
+                    let content = match &templ.name {
+                        Some(name) => slots.named
+                            .iter()
+                            .find(|(slot, _)| slot.name.as_ref() == Some(name))
+                            .map(|(_, stmts)| &stmts[..]),
+                        None => Some(slots.content).filter(|stmts| !is_blank(stmts)),
+                    };
+                    content.map(|stmts| (stmts, slots.caller))
+                });
+                let (stmts, shared) = fill.unwrap_or((stmts, shared));
+
+                token::Brace::default()
+                    .surround(self.tokens, |tokens|{
+                        let mut visitor = Visitor { tokens, static_len: self.static_len  };
+                        visitor.visit_stmts(stmts, shared);
+                        self.static_len = visitor.static_len;
+                    });
+            },
+            Scope::Block { .. } => unreachable!("`block` scope should be replaced with `render`"),
+            Scope::Macro { .. } => unreachable!("`macro` scope should be discarded"),
         }
     }
 }
 
+/// Returns `true` if statements only contains whitespace, which does not fill the unnamed slot.
+fn is_blank(stmts: &[StmtTempl]) -> bool {
+    stmts.iter().all(|stmt| matches!(
+        stmt,
+        StmtTempl::Scalar(Scalar::Static { value, .. }) if value.trim().is_empty()
+    ))
+}
+
+/// Name of the generated function for a template macro.
+pub fn macro_fn(name: &Ident) -> Ident {
+    format_ident!("__tour_macro_{name}")
+}
+
+/// Name of the generated function parameter for a template macro argument.
+pub fn macro_arg(index: usize) -> Ident {
+    format_ident!("__tour_arg{index}")
+}
+
+/// `Parser::with_syntax()` call for non default [`Syntax`].
+fn syntax(syntax: &Syntax) -> TokenStream {
+    if syntax.is_default() {
+        return quote! {};
+    }
+
+    let delims = Delimiter::ALL.into_iter().map(|delim| {
+        let name = format_ident!("{delim:?}");
+        match syntax.get(delim) {
+            Some((open, close)) => quote! { .with(::tour::Delimiter::#name, #open, #close) },
+            None => quote! { .without(::tour::Delimiter::#name) },
+        }
+    });
+
+    quote! { .with_syntax(::tour::Syntax::new() #(#delims)*) }
+}
+
 fn display(delim: Delimiter, expr: &syn::Expr) -> TokenStream {
     use Delimiter::*;
 
     match delim {
-        Quest => quote! {&::tour::Debug(&#expr)},
-        Percent => quote! {&::tour::Display(&#expr)},
-        Brace | Bang | Hash => quote! {&#expr},
+        Quest => quote! {&::tour::Debug(&(#expr))},
+        Percent => quote! {&::tour::Display(&(#expr))},
+        Brace | Bang => quote! {&(#expr)},
+        Hash => unreachable!("comment should be discarded by parser"),
     }
 }
 
@@ -263,6 +715,7 @@ fn writer(delim: Delimiter) -> TokenStream {
 
     match delim {
         Bang => quote! {&mut *writer},
-        Brace | Percent | Quest | Hash => quote! {&mut ::tour::Escape(&mut *writer)},
+        Brace | Percent | Quest => quote! {&mut ::tour::Escape(&mut *writer)},
+        Hash => unreachable!("comment should be discarded by parser"),
     }
 }
@@ -4,7 +4,7 @@ use syn::Token;
 
 use super::paren;
 use crate::{
-    ast::{Scalar, Scope, StmtTempl},
+    ast::{Scalar, Scope, StmtTempl, any_stmt},
     data::Template,
     file::AliasKind,
     syntax::{RenderTempl, RenderValue},
@@ -30,11 +30,13 @@ pub type SizeHint = (usize, Option<usize>);
 
 pub struct Visitor<'a> {
     templ: &'a Template,
+    /// blocks overridden by the template extending this layout
+    overrides: Vec<&'a syn::Ident>,
 }
 
 impl<'a> Visitor<'a> {
     pub fn new(templ: &'a Template) -> Self {
-        Self { templ }
+        Self { templ, overrides: vec![] }
     }
  
     pub fn calculate(&self) -> SizeHint {
@@ -45,6 +47,11 @@ impl<'a> Visitor<'a> {
         self.visit_stmts(&self.templ.file().block(block).stmts)
     }
 
+    /// Initial buffer capacity for `capture` scope.
+    pub fn capacity(&self, stmts: &[StmtTempl]) -> usize {
+        self.visit_stmts(stmts).0
+    }
+
     fn visit_stmts(&self, stmts: &[StmtTempl]) -> SizeHint {
         let mut size = (0,None);
         for stmt in stmts {
@@ -57,17 +64,34 @@ impl<'a> Visitor<'a> {
         match stmt {
             StmtTempl::Scalar(scalar) => match scalar {
                 Scalar::Static { value, .. } => exact(value.len()),
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), with: Some(_), .. })
+                    if self.templ.file().is_recursion(path) => (0,None),
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), block, with: Some(_), .. }) => {
+                    let templ = self.templ.file().partial(path).templ();
+                    let me = Visitor::new(templ);
+                    match block {
+                        Some((_, block)) => me.visit_stmts(&templ.file().block(block).stmts),
+                        None => me.visit_stmts(templ.stmts()),
+                    }
+                },
+                Scalar::Render(RenderTempl { value: RenderValue::Struct(_), .. }) => (0,None),
                 Scalar::Render(RenderTempl { value: RenderValue::Ident(id), block, .. }) => {
                     match (self.templ.file().resolve_id(id), block) {
+                        // overriding block is not known to the layout
+                        (AliasKind::Block(_), None) if self.overrides.contains(&id) => (0,None),
                         (AliasKind::Block(block), None) => self.visit_stmts(&block.stmts),
                         (AliasKind::Block(_), Some(_)) => unreachable!("cannot render block from block"),
                         (AliasKind::Import(import), None) => {
-                            let me = Visitor { templ: import.templ() };
+                            let mut me = Visitor::new(import.templ());
+                            if self.templ.file().layout_import().is_some_and(|layout| layout.path() == import.path()) {
+                                me.overrides.extend(self.overrides.iter().copied());
+                                me.overrides.extend(self.templ.file().blocks().iter().map(|block| &block.templ.name));
+                            }
                             me.visit_stmts(import.templ().stmts())
                         },
                         (AliasKind::Import(import), Some((_, block))) => {
                             let block = import.templ().file().block(block);
-                            let me = Visitor { templ: import.templ() };
+                            let me = Visitor::new(import.templ());
                             me.visit_stmts(&block.stmts)
                         },
                     }
@@ -84,12 +108,22 @@ impl<'a> Visitor<'a> {
                         },
                     }
                 },
-                Scalar::Yield(_) | Scalar::Expr { .. } | Scalar::Use(_) | Scalar::Item(_) => (0,None),
+                Scalar::Yield(_) | Scalar::Super { .. } | Scalar::Call(_) | Scalar::Expr { .. } | Scalar::Use(_) | Scalar::Control(_) | Scalar::Let(_) | Scalar::Item(_) => (0,None),
             },
             StmtTempl::Scope(scope) => self.visit_scope(scope),
         }
     }
 
+    fn visit_loop(&self, stmts: &[StmtTempl]) -> SizeHint {
+        let (min, max) = self.visit_stmts(stmts);
+
+        // `break` or `continue` may skip the rest of the body
+        match any_stmt(stmts, &|stmt| matches!(stmt, StmtTempl::Scalar(Scalar::Control(_)))) {
+            true => (0, max),
+            false => (min, max),
+        }
+    }
+
     fn visit_scope(&self, scope: &Scope) -> SizeHint {
         match scope {
             Scope::Root { stmts } => self.visit_stmts(stmts),
@@ -111,7 +145,7 @@ impl<'a> Visitor<'a> {
                 //
                 // more complex would be using `Iterator::size_hint`
 
-                let main_size = self.visit_stmts(stmts);
+                let main_size = self.visit_loop(stmts);
 
                 let else_size = match else_branch {
                     Some((_, else_scope)) => self.visit_scope(else_scope),
@@ -120,7 +154,34 @@ impl<'a> Visitor<'a> {
 
                 merge(main_size, else_size)
             },
+            Scope::With { stmts, else_branch, .. } => {
+                let else_size = match else_branch {
+                    Some((_, else_scope)) => self.visit_scope(else_scope),
+                    _ => (0, None),
+                };
+
+                merge(self.visit_stmts(stmts), else_size)
+            },
+            Scope::While { stmts, .. } | Scope::Loop { stmts, .. } => {
+                // same as `for`, the body may not be run, or break early
+                merge(self.visit_loop(stmts), (0, None))
+            },
+            Scope::Match { arms, .. } => {
+                arms.iter()
+                    .map(|(_, stmts)| self.visit_stmts(stmts))
+                    .reduce(merge)
+                    .unwrap_or((0, None))
+            },
+            Scope::Yield { templ, stmts } => match &templ.block {
+                Some((_, block)) if self.overrides.contains(&block) => (0, None),
+                _ => self.visit_stmts(stmts),
+            },
+            // slots may be filled by the caller
+            Scope::Render { .. } | Scope::Slot { .. } => (0, None),
+            // captured content is not written, see `capacity`
+            Scope::Capture { .. } => (0, None),
             Scope::Block { .. } => unreachable!("`block` scope should be replaced with `render`"),
+            Scope::Macro { .. } => unreachable!("`macro` scope should be discarded"),
         }
     }
 }
@@ -28,6 +28,20 @@ pub const DERIVE_ATTRIBUTE: &str = "template";
 /// Reserved block name containing template without the layout.
 pub const INNER_BLOCK: &str = "inner";
 
+/// Binding of the value in typed partial, `self` in the partial is rewritten to this.
+pub const PARTIAL_SELF: &str = "__partial";
+
+/// Builtin filters in `tour::filters`, other filters resolved from `filters` module in scope.
+pub const FILTERS: &[&str] = &["upper", "lower", "trim", "truncate", "safe"];
+
+/// Combine multiple errors into one, returns [`None`] if there is no error.
+pub(crate) fn combine(errors: impl IntoIterator<Item = syn::Error>) -> Option<syn::Error> {
+    errors.into_iter().reduce(|mut acc, err| {
+        acc.combine(err);
+        acc
+    })
+}
+
 pub(crate) fn name() -> syn::Ident {
     use std::sync::atomic::{AtomicUsize, Ordering};
     static COUNTER: AtomicUsize = AtomicUsize::new(0);
@@ -57,6 +71,12 @@ pub(crate) mod path {
         std::env::current_dir().expect("current dir")
     }
 
+    /// Path for display, relative to current directory if possible.
+    pub fn display(path: &str) -> &str {
+        path.trim_start_matches(cwd().to_str().unwrap_or(""))
+            .trim_start_matches("/")
+    }
+
     pub fn boxed(buf: PathBuf) -> Rc<str> {
         buf.to_string_lossy().into()
     }
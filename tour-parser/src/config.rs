@@ -1,20 +1,161 @@
 //! Shared configuration.
+//!
+//! Configuration is loaded from `tour.conf` in the current directory, if exists.
+//!
+//! ```text
+//! # templates directory
+//! templates = example/templates
+//!
+//! # delimiter syntax, `<open> <close>`, or empty to disable
+//! brace = <% %>
+//! hash = <%# %>
+//! percent =
+//!
+//! # items available in all templates, closing `]` in its own line
+//! prelude = [
+//!     use crate::frontend::*;
+//! ]
+//! ```
+//!
+//! Delimiter keys are `brace`, `bang`, `percent`, `quest` and `hash`, corresponding to
+//! [`Delimiter`] variants.
+use syn::{Item, Result};
+use tour_core::{Delimiter, Syntax};
+
+use crate::common::{error, path};
+
+/// Configuration file name.
+pub const CONFIG_FILE: &str = "tour.conf";
 
 pub struct Config {
+    /// path of the loaded configuration file
+    file: Option<Box<str>>,
     templ_dir: Box<str>,
+    syntax: Syntax,
+    prelude: Vec<Item>,
 }
 
 impl Config {
+    /// Load configuration from [`CONFIG_FILE`], or use the default if it does not exists.
+    pub fn load() -> Result<Config> {
+        let mut me = Config::default();
+
+        let path = path::cwd().join(CONFIG_FILE);
+        if !path.is_file() {
+            return Ok(me);
+        }
+
+        let source = error!(!std::fs::read_to_string(&path), "cannot read `{CONFIG_FILE}`: {}");
+        me.file = Some(path.to_string_lossy().into());
+
+        let mut lines = source.lines().enumerate();
+        while let Some((i, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                error!("{CONFIG_FILE}:{}: expected `key = value`", i + 1)
+            };
+
+            match (key.trim(), value.trim()) {
+                ("templates", value) => me.templ_dir = value.into(),
+                ("prelude", value) => {
+                    let Some(value) = value.strip_prefix('[') else {
+                        error!("{CONFIG_FILE}:{}: expected `[`", i + 1)
+                    };
+                    let content = match value.strip_suffix(']') {
+                        Some(content) => content.to_owned(),
+                        None => {
+                            let mut content = value.to_owned();
+                            loop {
+                                let Some((_, line)) = lines.next() else {
+                                    error!("{CONFIG_FILE}:{}: unclosed `prelude`, expected `]`", i + 1)
+                                };
+                                if line.trim() == "]" {
+                                    break;
+                                }
+                                content.push('\n');
+                                content.push_str(line);
+                            }
+                            content
+                        }
+                    };
+                    let file = error!(!syn::parse_file(&content), "{CONFIG_FILE}:{}: invalid `prelude`: {}", i + 1);
+                    me.prelude.extend(file.items);
+                },
+                (key, value) => match delimiter(key) {
+                    Some(delim) => match syntax_pair(value) {
+                        Some(pair) => me.syntax = with_delimiter(me.syntax, delim, pair),
+                        None => error!("{CONFIG_FILE}:{}: expected `<open> <close>` or empty", i + 1),
+                    },
+                    None => error!("{CONFIG_FILE}:{}: unknown key `{key}`", i + 1),
+                },
+            }
+        }
+
+        Ok(me)
+    }
+
+    /// Returns the loaded configuration file path, if exists.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
     pub fn templ_dir(&self) -> &str {
         &self.templ_dir
     }
+
+    /// Returns the default delimiter [`Syntax`].
+    pub fn syntax(&self) -> &Syntax {
+        &self.syntax
+    }
+
+    /// Returns items available in all templates.
+    pub fn prelude(&self) -> &[Item] {
+        &self.prelude
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            file: None,
             templ_dir: String::from("templates").into_boxed_str(),
+            syntax: Syntax::new(),
+            prelude: vec![],
         }
     }
 }
 
+/// Returns [`Delimiter`] by its configuration name.
+pub(crate) fn delimiter(name: &str) -> Option<Delimiter> {
+    match name {
+        "brace" => Some(Delimiter::Brace),
+        "bang" => Some(Delimiter::Bang),
+        "percent" => Some(Delimiter::Percent),
+        "quest" => Some(Delimiter::Quest),
+        "hash" => Some(Delimiter::Hash),
+        _ => None,
+    }
+}
+
+/// Parse `<open> <close>` delimiter pair, empty value means disabled.
+///
+/// Returns [`None`] if value is invalid.
+pub(crate) fn syntax_pair(value: &str) -> Option<Option<(String, String)>> {
+    let mut iter = value.split_whitespace();
+    match (iter.next(), iter.next(), iter.next()) {
+        (None, _, _) => Some(None),
+        (Some(open), Some(close), None) => Some(Some((open.into(), close.into()))),
+        _ => None,
+    }
+}
+
+pub(crate) fn with_delimiter(syntax: Syntax, delim: Delimiter, pair: Option<(String, String)>) -> Syntax {
+    match pair {
+        Some((open, close)) => syntax.with(delim, open, close),
+        None => syntax.without(delim),
+    }
+}
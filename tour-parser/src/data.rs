@@ -1,6 +1,6 @@
 //! The [`Template`] struct.
 use syn::*;
-use crate::{ast::StmtTempl, file::File, metadata::Metadata};
+use crate::{ast::StmtTempl, file::{File, Import}, metadata::Metadata};
 mod validate;
 
 /// Contains a single file template information.
@@ -35,6 +35,22 @@ impl Template {
         self.file.stmts()
     }
 
+    /// Returns layouts up to the nearest one which declares given block.
+    ///
+    /// Returns `None` if no layout declares the block.
+    pub(crate) fn layout_chain(&self, block: &Ident) -> Option<Vec<&Import>> {
+        let mut chain = vec![];
+        let mut templ = self;
+        while let Some(layout) = templ.file.layout_import() {
+            chain.push(layout);
+            templ = layout.templ();
+            if templ.file.get_block(block).is_some() {
+                return Some(chain);
+            }
+        }
+        None
+    }
+
     /// Returns template name.
     ///
     /// Template name is from either derive macro ident, aliased, or auto generated.
@@ -6,10 +6,10 @@ use crate::{ast::*, common::{error, INNER_BLOCK}, file::BlockContent, syntax::*}
 
 pub fn validate(templ: &mut Template) -> Result<()> {
     // check if selected block exists
-    if let Some(block) = templ.meta.block() {
-        if templ.file.get_block(block).is_none() {
-            error!("cannot find `{block}` in `{}`",templ.name)
-        }
+    if let Some(block) = templ.meta.block()
+        && templ.file.get_block(block).is_none()
+    {
+        error!("cannot find `{block}` in `{}`",templ.name)
     }
 
     // no inner block reserved name
@@ -17,15 +17,37 @@ pub fn validate(templ: &mut Template) -> Result<()> {
         error!(block.templ.name, "`{INNER_BLOCK}` is reserved block name")
     }
 
+    // `super` requires the block to be declared in layout
+    for block in templ.file.blocks() {
+        let is_super = |stmt: &StmtTempl| matches!(stmt, StmtTempl::Scalar(Scalar::Super { .. }));
+        let name = &block.templ.name;
+        if any_stmt(&block.stmts, &is_super) && templ.layout_chain(name).is_none() {
+            error!(name, "cannot use `super`, block `{name}` is not declared in any layout")
+        }
+    }
+
     // if uses layout, make inner body as a block
-    if let Some(layout) = templ.file.layout() {
-        let name = templ.file.import_by_path(&layout.path).alias();
+    if let Some(path) = templ.file.layout().map(|layout| layout.path.clone()) {
+        // block overriding the layout is rendered by the layout instead
+        let overrides: Vec<_> = templ.file.blocks()
+            .iter()
+            .map(|block| block.templ.name.clone())
+            .filter(|name| templ.layout_chain(name).is_some())
+            .collect();
+        retain_stmt(templ.file.stmts_mut(), &|stmt| !matches!(
+            stmt,
+            StmtTempl::Scalar(Scalar::Render(RenderTempl { value: RenderValue::Ident(id), block: None, with: None, .. }))
+                if overrides.contains(id)
+        ));
+
+        let name = templ.file.import_by_path(&path).alias();
 
         let mut inner = vec![
             StmtTempl::Scalar(Scalar::Render(RenderTempl {
                 render_token: <_>::default(),
                 value: RenderValue::Ident(name.clone()),
                 block: None,
+                with: None,
             })),
         ];
 
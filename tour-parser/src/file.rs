@@ -6,7 +6,7 @@ use crate::{
     ast::StmtTempl,
     data::Template,
     metadata::Metadata,
-    syntax::{BlockTempl, LayoutTempl},
+    syntax::{BlockTempl, ItemTempl, LayoutTempl, MacroTempl},
 };
 
 mod visitor;
@@ -19,8 +19,15 @@ use validate::ValidateVisitor;
 pub struct File {
     layout: Option<LayoutTempl>,
     imports: Vec<Import>,
+    /// typed partials, rendered with `with`
+    partials: Vec<Import>,
     blocks: Vec<BlockContent>,
+    macros: Vec<MacroContent>,
+    /// toplevel items, visible to all blocks
+    items: Vec<Rc<ItemTempl>>,
     statics: Vec<Rc<str>>,
+    /// typed partials rendering a template which is currently being loaded
+    recursions: Vec<Recursion>,
     stmts: Vec<StmtTempl>,
 }
 
@@ -29,6 +36,11 @@ pub struct BlockContent {
     pub stmts: Vec<StmtTempl>,
 }
 
+pub struct MacroContent {
+    pub templ: MacroTempl,
+    pub stmts: Vec<StmtTempl>,
+}
+
 impl File {
     /// Create [`File`] from [`Metadata`].
     pub fn from_meta(meta: &Metadata) -> Result<File> {
@@ -46,12 +58,17 @@ impl File {
         self.get_block(block).expect("[BUG] validation block rendering missed")
     }
 
+    /// Get macro by name.
+    pub fn get_macro(&self, name: &Ident) -> Option<&MacroContent> {
+        self.macros.iter().find(|e| &e.templ.name == name)
+    }
+
     /// Get imported template by id.
     pub fn get_import_by_id(&self, name: &Ident) -> Option<&Import> {
         self.imports.iter().find(|&e| e == name)
     }
 
-    fn import_by_id(&self, name: &Ident) -> &Import {
+    pub(crate) fn import_by_id(&self, name: &Ident) -> &Import {
         self.get_import_by_id(name)
             .unwrap_or_else(|| panic!("[BUG] validation import id missed, cannot find `{name}`: {:#?}",self.imports()))
     }
@@ -71,6 +88,41 @@ impl File {
         })
     }
 
+    /// Get typed partial by path.
+    pub fn get_partial(&self, path: &LitStr) -> Option<&Import> {
+        let path = path.value();
+        self.partials.iter().find(|&e| e == &*path)
+    }
+
+    pub(crate) fn partial(&self, path: &LitStr) -> &Import {
+        self.get_partial(path).unwrap_or_else(|| {
+            panic!(
+                "[BUG] validation partial path missed, cannot find `{}`",
+                path.value()
+            )
+        })
+    }
+
+    /// Returns `true` if typed partial recursively render a template being loaded.
+    pub fn is_recursion(&self, path: &LitStr) -> bool {
+        self.get_recursion(path).is_some()
+    }
+
+    /// Get recursive typed partial by path.
+    pub fn get_recursion(&self, path: &LitStr) -> Option<&Recursion> {
+        let path = path.value();
+        self.recursions.iter().find(|e| *e.path == *path)
+    }
+
+    pub(crate) fn recursion(&self, path: &LitStr) -> &Recursion {
+        self.get_recursion(path).unwrap_or_else(|| {
+            panic!(
+                "[BUG] validation recursion path missed, cannot find `{}`",
+                path.value()
+            )
+        })
+    }
+
     pub(crate) fn get_resolved_id(&self, id: &Ident) -> Option<AliasKind<'_>> {
         match self.get_block(id) {
             Some(block) => Some(AliasKind::Block(block)),
@@ -99,6 +151,10 @@ impl File {
         &self.imports
     }
 
+    pub fn partials(&self) -> &[Import] {
+        &self.partials
+    }
+
     pub fn blocks(&self) -> &[BlockContent] {
         &self.blocks
     }
@@ -107,6 +163,14 @@ impl File {
         &mut self.blocks
     }
 
+    pub fn macros(&self) -> &[MacroContent] {
+        &self.macros
+    }
+
+    pub fn items(&self) -> &[Rc<ItemTempl>] {
+        &self.items
+    }
+
     pub fn statics(&self) -> &[Rc<str>] {
         &self.statics
     }
@@ -114,6 +178,11 @@ impl File {
     pub fn layout(&self) -> Option<&LayoutTempl> {
         self.layout.as_ref()
     }
+
+    /// Returns the imported layout template.
+    pub fn layout_import(&self) -> Option<&Import> {
+        self.layout.as_ref().map(|layout| self.import_by_path(&layout.path))
+    }
 }
 
 // ===== Import =====
@@ -151,6 +220,28 @@ impl PartialEq<Ident> for Import {
     }
 }
 
+// ===== Recursion =====
+
+/// Typed partial rendering a template which is currently being loaded.
+///
+/// The template is the derived template or an enclosing typed partial.
+#[derive(Debug)]
+pub struct Recursion {
+    path: Rc<str>,
+    target: Rc<str>,
+}
+
+impl Recursion {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Resolved path of the rendered template.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+}
+
 // ===== AliasKind =====
 
 pub enum AliasKind<'a> {
@@ -165,13 +256,25 @@ impl std::fmt::Debug for File {
         f.debug_struct("File")
             .field("layout", &self.layout.as_ref().map(|e|e.path.value()))
             .field("imports", &self.imports)
+            .field("partials", &self.partials)
             .field("blocks", &self.blocks)
+            .field("macros", &self.macros)
+            .field("items", &self.items.len())
             .field("statics", &self.statics)
             .field("stmts", &"<statements>")
             .finish()
     }
 }
 
+impl std::fmt::Debug for MacroContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MacroContent")
+            .field("templ", &self.templ.name)
+            .field("stmts", &"<statements>")
+            .finish()
+    }
+}
+
 impl std::fmt::Debug for BlockContent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("BlockContent")
@@ -1,10 +1,10 @@
-use syn::Result;
+use syn::{Result, spanned::Spanned as _};
 
 use super::{AliasKind, File};
 use crate::{
-    ast::{Scalar, Scope, StmtTempl},
+    ast::{Scalar, Scope, StmtTempl, any_stmt},
     common::error,
-    syntax::{RenderTempl, RenderValue},
+    syntax::{CallTempl, RenderTempl, RenderValue},
 };
 
 /// Template validation.
@@ -14,6 +14,10 @@ use crate::{
 /// Will validate:
 ///
 /// - the referenced template by `render` statement exists
+/// - the called macro by `call` statement exists and receives matching arguments
+/// - the typed partial by `render .. with` statement exists and is self contained, unless it is
+///   recursive
+/// - the template by `render` scope exists and contains the filled slots
 pub struct ValidateVisitor<'a> {
     file: &'a File,
 }
@@ -22,7 +26,11 @@ impl<'a> ValidateVisitor<'a> {
     /// Start validating.
     pub fn validate(file: &'a File) -> Result<()> {
         let me = Self { file };
-        me.visit_stmts(&me.file.stmts)
+        me.visit_stmts(&me.file.stmts)?;
+        for mac in &me.file.macros {
+            me.visit_stmts(&mac.stmts)?;
+        }
+        Ok(())
     }
 
     fn visit_stmts(&self, stmts: &[StmtTempl]) -> Result<()> {
@@ -37,6 +45,33 @@ impl<'a> ValidateVisitor<'a> {
             StmtTempl::Scalar(scalar) => match scalar {
                 Scalar::Static { .. } => {}
                 Scalar::Use(_) => {}
+                // the value is checked by its `Template` implementation
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), with: Some(_), .. })
+                    if self.file.is_recursion(path) => {}
+                Scalar::Render(RenderTempl { value: RenderValue::Path(path), block, with: Some(_), .. }) => {
+                    let Some(partial) = self.file.get_partial(path) else {
+                        error!(path, "cannot find template `{}`", path.value())
+                    };
+                    let file = partial.templ.file();
+                    if let Some((_, block)) = block
+                        && file.get_block(block).is_none()
+                    {
+                        error!(path, "cannot find block `{block}` in `{}`", path.value())
+                    }
+                    // partial is rendered in place, it cannot reference its own generated types
+                    if file.layout().is_some() || !file.imports().is_empty() || !file.macros().is_empty() {
+                        error!(
+                            path,
+                            "typed partial `{}` cannot contain `layout`, `use` or `macro`",
+                            path.value(),
+                        )
+                    }
+                    ValidateVisitor::validate(file)?;
+                },
+                Scalar::Render(RenderTempl { with: Some((with, _)), .. }) => {
+                    error!(with, "`with` requires a template path, e.g. `render \"card.html\" with value`")
+                },
+                Scalar::Render(RenderTempl { value: RenderValue::Struct(_), .. }) => {}
                 Scalar::Render(RenderTempl { value: RenderValue::Ident(id), block, .. }) => {
                     match (self.file.get_resolved_id(id),block) {
                         (Some(_), None) => {
@@ -70,7 +105,30 @@ impl<'a> ValidateVisitor<'a> {
                         },
                     }
                 },
+                Scalar::Call(CallTempl { alias, name, args, .. }) => {
+                    let file = match alias {
+                        Some((alias, _)) => match self.file.get_import_by_id(alias) {
+                            Some(import) => import.templ.file(),
+                            None => error!(alias, "cannot find template `{alias}`"),
+                        },
+                        None => self.file,
+                    };
+                    let Some(mac) = file.get_macro(name) else {
+                        error!(name, "cannot find macro `{name}`")
+                    };
+                    let expected = mac.templ.inputs.len();
+                    if args.len() != expected {
+                        error!(
+                            name,
+                            "macro `{name}` takes {expected} argument(s) but {} were supplied",
+                            args.len(),
+                        )
+                    }
+                },
                 Scalar::Yield(_) => {}
+                Scalar::Super { .. } => {}
+                Scalar::Control(_) => {}
+                Scalar::Let(_) => {}
                 Scalar::Item(_) => {}
                 Scalar::Expr { .. } => {}
             }
@@ -89,18 +147,70 @@ impl<'a> ValidateVisitor<'a> {
                     self.visit_scope(scope)?;
                 }
             },
-            Scope::For { stmts, else_branch, .. } => {
+            Scope::For { stmts, else_branch, .. } | Scope::With { stmts, else_branch, .. } => {
                 self.visit_stmts(stmts)?;
                 if let Some((_,scope)) = else_branch {
                     self.visit_scope(scope)?;
                 }
             },
-            Scope::Block { .. } => unreachable!("`block` scope should be replaced with `render`")
+            Scope::While { stmts, .. }
+            | Scope::Loop { stmts, .. }
+            | Scope::Capture { stmts, .. }
+            | Scope::Slot { stmts, .. }
+            | Scope::Yield { stmts, .. } => self.visit_stmts(stmts)?,
+            Scope::Render { templ, stmts, slots } => {
+                let import = match &templ.value {
+                    RenderValue::Path(path) => self.file.get_import_by_path(path),
+                    RenderValue::Ident(id) => match self.file.get_resolved_id(id) {
+                        Some(AliasKind::Import(import)) => Some(import),
+                        Some(AliasKind::Block(_)) => error!(id, "cannot fill slots of a block `{id}`"),
+                        None => None,
+                    },
+                    RenderValue::Struct(_) => unreachable!("`render` scope requires a template"),
+                };
+                let Some(import) = import else {
+                    error!(templ.render_token, "cannot find template `{}`", import_name(&templ.value))
+                };
+                let file = import.templ.file();
+                if let Some((_, block)) = &templ.block
+                    && file.get_block(block).is_none()
+                {
+                    error!(block, "cannot find block `{block}` in `{}`", import_name(&templ.value))
+                }
+                for (slot, _) in slots {
+                    let name = slot.name.as_ref().expect("unnamed slot content is rejected");
+                    let is_slot = |stmt: &StmtTempl| matches!(
+                        stmt,
+                        StmtTempl::Scope(Scope::Slot { templ, .. }) if templ.name.as_ref() == Some(name)
+                    );
+                    let is_found = any_stmt(&file.stmts, &is_slot)
+                        || file.blocks.iter().any(|block| any_stmt(&block.stmts, &is_slot));
+                    if !is_found {
+                        error!(name, "cannot find slot `{name}` in `{}`", import_name(&templ.value))
+                    }
+                }
+                self.visit_stmts(stmts)?;
+                for (_, stmts) in slots {
+                    self.visit_stmts(stmts)?;
+                }
+            },
+            Scope::Match { arms, .. } => {
+                for (_, stmts) in arms {
+                    self.visit_stmts(stmts)?;
+                }
+            },
+            Scope::Block { .. } => unreachable!("`block` scope should be replaced with `render`"),
+            Scope::Macro { .. } => unreachable!("`macro` scope should be discarded"),
         }
 
         Ok(())
     }
 }
 
-
-
+fn import_name(value: &RenderValue) -> String {
+    match value {
+        RenderValue::Path(path) => path.value(),
+        RenderValue::Ident(id) => id.to_string(),
+        RenderValue::Struct(_) => unreachable!("`render` scope requires a template"),
+    }
+}
@@ -1,13 +1,15 @@
 //! [`Visitor`] implementation via syn
+use proc_macro2::{Group, TokenStream, TokenTree};
 use std::rc::Rc;
-use syn::*;
-use tour_core::{Delimiter, ParseError, Parser, Result, Visitor};
+use syn::{parse::ParseStream, *};
+use tour_core::{Delimiter, ParseError, Parser, Result, Span, Visitor};
 
-use super::{BlockContent, File, Import};
+use super::{BlockContent, File, Import, MacroContent, Recursion};
 use crate::{
     ast::{Scalar, Scope, StmtTempl},
+    common::{PARTIAL_SELF, combine, path},
     data::Template,
-    metadata::Metadata,
+    metadata::{Metadata, TemplKind},
     syntax::*,
 };
 
@@ -22,30 +24,76 @@ macro_rules! error {
 pub struct SynVisitor<'a> {
     layout: Option<LayoutTempl>,
     imports: Vec<Import>,
+    partials: Vec<Import>,
     blocks: Vec<BlockContent>,
+    macros: Vec<MacroContent>,
+    items: Vec<Rc<ItemTempl>>,
     statics: Vec<Rc<str>>,
+    recursions: Vec<Recursion>,
     root: Vec<StmtTempl>,
 
     /// currently open scopes
     scopes: Vec<Scope>,
+    /// templates currently being loaded, including this one
+    loading: Vec<(Rc<str>, TemplKind)>,
     meta: &'a Metadata,
+    /// errors from imported templates
+    errors: &'a mut Vec<syn::Error>,
 }
 
 impl<'a> SynVisitor<'a> {
+    /// Generate [`File`], reporting all errors found.
     pub fn generate(meta: &Metadata) -> syn::Result<File> {
+        Self::generate_nested(meta, vec![])
+    }
+
+    fn generate_nested(meta: &Metadata, mut loading: Vec<(Rc<str>, TemplKind)>) -> syn::Result<File> {
         let source = meta.resolve_source()?;
+        let mut errors = vec![];
+        if meta.is_file() {
+            loading.push((meta.path().into(), *meta.kind()));
+        }
         let visitor = SynVisitor {
             layout: None,
             imports: vec![],
+            partials: vec![],
             blocks: vec![],
+            macros: vec![],
+            items: vec![],
             statics: vec![],
+            recursions: vec![],
             root: vec![],
             scopes: vec![],
+            loading,
             meta,
+            errors: &mut errors,
+        };
+
+        let result = Parser::new(source.as_ref(), visitor)
+            .with_syntax(meta.syntax().clone())
+            .parse_all()
+            .map(|me| {
+                let SynVisitor {
+                    layout, imports, partials, blocks, macros, items, statics, recursions, root, ..
+                } = me;
+                File { layout, imports, partials, blocks, macros, items, statics, recursions, stmts: root }
+            });
+
+        let parse_errors = match result {
+            Ok(file) if errors.is_empty() => return Ok(file),
+            Ok(_) => vec![],
+            Err(parse_errors) => parse_errors,
         };
-        let me = crate::common::error!(!Parser::new(source.as_ref(), visitor).parse());
-        let SynVisitor { layout, imports, blocks, statics, root, .. } = me;
-        Ok(File { layout, imports, blocks, statics, stmts: root })
+
+        let parse_errors = parse_errors.into_iter().map(|err| {
+            let message = match meta.is_file() {
+                true => format!("{err}, in `{}`", path::display(meta.path())),
+                false => err.to_string(),
+            };
+            syn::Error::new(proc_macro2::Span::call_site(), message)
+        });
+
+        Err(combine(parse_errors.chain(errors)).expect("at least one error"))
     }
 
     fn stack_mut(&mut self) -> &mut Vec<StmtTempl> {
@@ -55,6 +103,86 @@ impl<'a> SynVisitor<'a> {
         }
     }
 
+    /// Returns `true` if currently in `match` scope before the first `when`.
+    fn is_match_prelude(&self) -> bool {
+        matches!(self.scopes.last(), Some(Scope::Match { arms, .. }) if arms.is_empty())
+    }
+
+    /// Returns `true` if `break` or `continue` is allowed in current scope.
+    fn is_in_loop(&self) -> bool {
+        self.scopes
+            .iter()
+            .rev()
+            // block and macro are also rendered as separate function
+            .take_while(|scope| !matches!(scope, Scope::Block { .. } | Scope::Macro { .. }))
+            .any(|scope| match scope {
+                // `else` branch is generated outside the loop
+                Scope::For { else_branch, .. } => else_branch.is_none(),
+                Scope::While { .. } | Scope::Loop { .. } => true,
+                _ => false,
+            })
+    }
+
+    /// Returns the number of innermost scopes that can be closed implicitly.
+    ///
+    /// `render`, `slot` and `yield` without its closing statement is a scalar statement, except
+    /// for slot content directly in `render` scope, which requires `endslot`.
+    fn optional_scopes(&self) -> usize {
+        self.scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|&(i, scope)| match scope {
+                Scope::Render { .. } | Scope::Yield { .. } => true,
+                Scope::Slot { .. } => !matches!(
+                    i.checked_sub(1).map(|i| &self.scopes[i]),
+                    Some(Scope::Render { .. })
+                ),
+                _ => false,
+            })
+            .count()
+    }
+
+    /// Returns `true` if there is no open scope other than the implicitly closed one.
+    fn is_toplevel(&self) -> bool {
+        self.optional_scopes() == self.scopes.len()
+    }
+
+    /// Close `render`, `slot` and `yield` scopes that turns out to be a scalar statement.
+    fn close_optional(&mut self) -> Result<()> {
+        for _ in 0..self.optional_scopes() {
+            let stmts = match self.scopes.pop().expect("counted") {
+                Scope::Render { templ, stmts, slots } => {
+                    if !slots.is_empty() {
+                        error!("unclosed `render` scope, expected `endrender`")
+                    }
+                    self.stack_mut().push(StmtTempl::Scalar(Scalar::Render(templ)));
+                    stmts
+                },
+                Scope::Slot { templ, stmts } => {
+                    self.stack_mut().push(StmtTempl::Scope(Scope::Slot { templ, stmts: vec![] }));
+                    stmts
+                },
+                Scope::Yield { templ, stmts } => {
+                    self.stack_mut().push(StmtTempl::Scalar(Scalar::Yield(templ)));
+                    stmts
+                },
+                _ => unreachable!("only `render`, `slot` and `yield` are optional"),
+            };
+            self.stack_mut().extend(stmts);
+        }
+        Ok(())
+    }
+
+    /// Pop currently open scope if it matches, otherwise the scope is kept open.
+    fn pop_scope(&mut self, close: &str, is_match: fn(&Scope) -> bool) -> Result<Scope> {
+        match self.scopes.last() {
+            Some(scope) if is_match(scope) => Ok(self.scopes.pop().expect("checked")),
+            Some(scope) => error!("cannot close `{close}` in `{scope}` scope"),
+            None => error!("cannot close `{close}` in toplevel"),
+        }
+    }
+
     fn import(&mut self, lit_str: &LitStr) -> Result<()> {
         self.import_only(lit_str, crate::common::name())
     }
@@ -68,40 +196,262 @@ impl<'a> SynVisitor<'a> {
 
         if !self.imports.iter().any(|e|e==&*path) {
             let meta = self.meta.clone_as_import(&*path);
-            let file = match Self::generate(&meta) {
-                Ok(ok) => ok,
-                Err(err) => return Err(ParseError::Generic(err.to_string())),
-            };
-            let templ = match Template::new(alias.clone(), meta, file) {
-                Ok(ok) => ok,
-                Err(err) => error!("{err}"),
-            };
-            self.imports.push(Import { path, alias, templ });
+            if self.loading_kind(&meta).is_some() {
+                error!("recursive template `{path}` can only be rendered with `render .. with`")
+            }
+            if let Some(templ) = self.load(meta, alias.clone())? {
+                self.imports.push(Import { path, alias, templ });
+            }
         }
 
         Ok(())
     }
+
+    fn partial(&mut self, path: &LitStr, block: Option<&Ident>) -> Result<()> {
+        let path: Rc<str> = path.value().into();
+        let meta = self.meta.clone_as_partial(&*path);
+
+        // recursion is rendered by the template which is being loaded
+        if let Some(kind) = self.loading_kind(&meta) {
+            match kind {
+                TemplKind::Partial if block.is_some() => {
+                    error!("recursive typed partial `{path}` cannot select a block")
+                },
+                TemplKind::Main | TemplKind::Partial => {},
+                _ => error!("recursive template `{path}` must be the derived template or a typed partial"),
+            }
+            if !self.recursions.iter().any(|e| e.path == path) {
+                self.recursions.push(Recursion { target: meta.path().into(), path });
+            }
+            return Ok(());
+        }
+
+        if !self.partials.iter().any(|e|e==&*path) {
+            let alias = crate::common::name();
+            if let Some(templ) = self.load(meta, alias.clone())? {
+                self.partials.push(Import { path, alias, templ });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the kind of the template if it is currently being loaded, which means it is
+    /// recursive.
+    fn loading_kind(&self, meta: &Metadata) -> Option<TemplKind> {
+        self.loading
+            .iter()
+            .find(|(path, _)| &**path == meta.path())
+            .map(|(_, kind)| *kind)
+    }
+
+    /// Returns `None` if the template contains error, which is already collected.
+    fn load(&mut self, meta: Metadata, alias: Ident) -> Result<Option<Template>> {
+        let file = match Self::generate_nested(&meta, self.loading.clone()) {
+            Ok(ok) => ok,
+            Err(err) => {
+                // errors is reported with its own location
+                self.errors.push(err);
+                return Ok(None);
+            },
+        };
+        match Template::new(alias, meta, file) {
+            Ok(ok) => Ok(Some(ok)),
+            Err(err) => error!("{err}"),
+        }
+    }
+}
+
+/// Rewrite `self` into the typed partial value.
+///
+/// Items are not rewritten, so `self` in its `impl` still works.
+fn partial_self(tokens: TokenStream) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|token| match token {
+            TokenTree::Ident(ident) if ident == "self" => {
+                TokenTree::Ident(Ident::new(PARTIAL_SELF, ident.span()))
+            }
+            TokenTree::Group(group) => {
+                let mut new = Group::new(group.delimiter(), partial_self(group.stream()));
+                new.set_span(group.span());
+                TokenTree::Group(new)
+            }
+            token => token,
+        })
+        .collect()
+}
+
+/// Open placeholder scope for invalid scoped statement, so the closing statement still match.
+fn invalid_scope(input: ParseStream) -> syn::Result<Option<Scope>> {
+    let scope = match () {
+        _ if input.peek(Token![if]) => Some(Scope::If {
+            templ: IfTempl {
+                if_token: input.parse()?,
+                cond: Rc::new(Expr::Verbatim(<_>::default())),
+            },
+            stmts: vec![],
+            else_branch: None,
+        }),
+        _ if input.peek(Token![for]) => Some(Scope::For {
+            templ: ForTempl {
+                label: None,
+                for_token: input.parse()?,
+                pat: Rc::new(Pat::Verbatim(<_>::default())),
+                in_token: <_>::default(),
+                expr: Rc::new(Expr::Verbatim(<_>::default())),
+                with: None,
+            },
+            stmts: vec![],
+            else_branch: None,
+        }),
+        _ if input.peek(Token![while]) => Some(Scope::While {
+            templ: WhileTempl {
+                label: None,
+                while_token: input.parse()?,
+                cond: Rc::new(Expr::Verbatim(<_>::default())),
+            },
+            stmts: vec![],
+        }),
+        _ if input.peek(Token![match]) => Some(Scope::Match {
+            templ: MatchTempl {
+                match_token: input.parse()?,
+                expr: Rc::new(Expr::Verbatim(<_>::default())),
+            },
+            arms: vec![],
+        }),
+        _ if input.peek(kw::with) => Some(Scope::With {
+            templ: WithTempl {
+                with_token: input.parse()?,
+                expr: Rc::new(Expr::Verbatim(<_>::default())),
+                as_token: <_>::default(),
+                pat: Rc::new(Pat::Verbatim(<_>::default())),
+            },
+            stmts: vec![],
+            else_branch: None,
+        }),
+        _ if input.peek(kw::capture) => Some(Scope::Capture {
+            templ: CaptureTempl {
+                capture_token: input.parse()?,
+                name: Ident::new("_", proc_macro2::Span::call_site()),
+            },
+            stmts: vec![],
+        }),
+        _ => None,
+    };
+    input.parse::<TokenStream>()?;
+    Ok(scope)
+}
+
+/// Rewrite `loop.` into `r#loop.`, so loop metadata can be accessed as `loop.index`.
+///
+/// `loop` followed by `.` is never a valid expression.
+fn raw_loop(tokens: TokenStream) -> TokenStream {
+    let mut tokens = tokens.into_iter().peekable();
+    let mut output = TokenStream::new();
+
+    while let Some(token) = tokens.next() {
+        let token = match token {
+            TokenTree::Ident(ident)
+                if ident == "loop"
+                    && matches!(tokens.peek(), Some(TokenTree::Punct(p)) if p.as_char() == '.') =>
+            {
+                TokenTree::Ident(Ident::new_raw("loop", ident.span()))
+            }
+            TokenTree::Group(group) => {
+                let mut new = Group::new(group.delimiter(), raw_loop(group.stream()));
+                new.set_span(group.span());
+                TokenTree::Group(new)
+            }
+            token => token,
+        };
+        output.extend([token]);
+    }
+
+    output
+}
+
+/// Placeholder for invalid `when` statement, so the following content is not rejected.
+fn invalid_arm(input: ParseStream) -> syn::Result<Option<WhenTempl>> {
+    let arm = match () {
+        _ if WhenTempl::peek(input) => Some(WhenTempl {
+            when_token: input.parse()?,
+            pat: Rc::new(Pat::Verbatim(<_>::default())),
+            guard: None,
+        }),
+        _ => None,
+    };
+    input.parse::<TokenStream>()?;
+    Ok(arm)
 }
 
 impl Visitor<'_> for SynVisitor<'_> {
-    fn visit_static(&mut self, source: &str) -> Result<()> {
+    fn visit_static(&mut self, source: &str, _: Span) -> Result<()> {
         let index = self.statics.len().try_into().unwrap();
+        self.statics.push(source.into());
+
+        // whitespace between `match` and the first `when` is discarded
+        if self.is_match_prelude() {
+            if !source.trim().is_empty() {
+                error!("expected `when` in `match` scope, found static content")
+            }
+            return Ok(());
+        }
 
         self.stack_mut().push(StmtTempl::Scalar(Scalar::Static {
             value: source.into(),
             index,
         }));
-        self.statics.push(source.into());
 
         Ok(())
     }
 
-    fn visit_expr(&mut self, source: &str, delim: Delimiter) -> Result<()> {
-        let expr = match syn::parse_str(source) {
-            Ok(ok) => ok,
+    fn visit_expr(&mut self, source: &str, delim: Delimiter, _: Span) -> Result<()> {
+        let tokens = match source.parse() {
+            Ok(ok) => raw_loop(ok),
             Err(err) => error!("failed to parse expr: {err}"),
         };
 
+        let tokens = match self.meta.kind() {
+            TemplKind::Partial if !parse::Parser::parse2(ItemTempl::peek_only, tokens.clone()).unwrap_or(false) => {
+                partial_self(tokens)
+            }
+            _ => tokens,
+        };
+
+        let expr = match syn::parse2(tokens) {
+            Ok(ok) => ok,
+            Err(err) => {
+                if let Ok(Some(scope)) = parse::Parser::parse_str(invalid_scope, source) {
+                    self.scopes.push(scope);
+                }
+                if let Some(Scope::Match { arms, .. }) = self.scopes.last_mut()
+                    && let Ok(Some(arm)) = parse::Parser::parse_str(invalid_arm, source)
+                {
+                    arms.push((arm, vec![]));
+                }
+                error!("failed to parse expr: {err}")
+            },
+        };
+
+        if self.is_match_prelude() && !matches!(expr, StmtSyn::When(_) | StmtSyn::EndMatch(_)) {
+            error!("expected `when` in `match` scope")
+        }
+
+        let is_closing = match expr {
+            StmtSyn::EndRender(_) => !matches!(self.scopes.last(), Some(Scope::Render { .. })),
+            StmtSyn::EndSlot(_) => !matches!(self.scopes.last(), Some(Scope::Slot { .. })),
+            StmtSyn::EndYield(_) => !matches!(self.scopes.last(), Some(Scope::Yield { .. })),
+            StmtSyn::Else(_) | StmtSyn::When(_) | StmtSyn::Endblock(_) | StmtSyn::EndMacro(_)
+            | StmtSyn::EndIf(_) | StmtSyn::EndFor(_) | StmtSyn::EndWhile(_) | StmtSyn::EndLoop(_)
+            | StmtSyn::EndMatch(_) | StmtSyn::EndWith(_) | StmtSyn::EndCapture(_) => true,
+            _ => false,
+        };
+
+        if is_closing {
+            self.close_optional()?;
+        }
+
         match expr {
             // ===== external reference =====
 
@@ -114,19 +464,59 @@ impl Visitor<'_> for SynVisitor<'_> {
             },
             StmtSyn::Use(templ) => self.import_aliased(&templ)?,
             StmtSyn::Render(templ) => {
-                if let RenderValue::Path(lit_str) = &templ.value {
-                    self.import(lit_str)?;
+                match (&templ.value, &templ.with) {
+                    (RenderValue::Path(lit_str), Some(_)) => {
+                        self.partial(lit_str, templ.block.as_ref().map(|(_, block)| block))?
+                    },
+                    (RenderValue::Path(lit_str), None) => self.import(lit_str)?,
+                    _ => {}
+                }
+                match (&templ.value, &templ.with) {
+                    // may be closed with `endrender` to fill slots
+                    (RenderValue::Path(_) | RenderValue::Ident(_), None) => {
+                        self.scopes.push(Scope::Render { templ, stmts: vec![], slots: vec![] });
+                    },
+                    _ => self.stack_mut().push(StmtTempl::Scalar(Scalar::Render(templ))),
                 }
-                self.stack_mut().push(StmtTempl::Scalar(Scalar::Render(templ)));
             },
 
             // ===== scalar =====
 
-            StmtSyn::Yield(templ) => {
-                self.stack_mut().push(StmtTempl::Scalar(Scalar::Yield(templ)));
+            StmtSyn::Call(templ) => {
+                self.stack_mut().push(StmtTempl::Scalar(Scalar::Call(templ)));
             },
-            StmtSyn::Item(item) => {
-                self.stack_mut().push(StmtTempl::Scalar(Scalar::Item(item)));
+            StmtSyn::Yield(templ) => match templ.block {
+                // may be closed with `endyield` to provide default content
+                Some(_) => self.scopes.push(Scope::Yield { templ, stmts: vec![] }),
+                None => self.stack_mut().push(StmtTempl::Scalar(Scalar::Yield(templ))),
+            },
+            StmtSyn::Super(templ) => {
+                let block = self.scopes.iter().rev().find_map(|scope| match scope {
+                    Scope::Block { templ, .. } => Some(templ.name.clone()),
+                    _ => None,
+                });
+                let Some(block) = block else {
+                    error!("`super` outside of a block")
+                };
+                self.stack_mut().push(StmtTempl::Scalar(Scalar::Super { templ, block }));
+            },
+            StmtSyn::Control(templ) => {
+                if !self.is_in_loop() {
+                    let name = match templ {
+                        ControlTempl::Break(..) => "break",
+                        ControlTempl::Continue(..) => "continue",
+                    };
+                    error!("`{name}` outside of a loop")
+                }
+                self.stack_mut().push(StmtTempl::Scalar(Scalar::Control(templ)));
+            },
+            StmtSyn::Let(templ) => {
+                self.stack_mut().push(StmtTempl::Scalar(Scalar::Let(templ)));
+            },
+            StmtSyn::Item(item) => match self.is_toplevel() {
+                // toplevel item is generated in every block
+                true => self.items.push(item),
+                false => self.stack_mut().push(StmtTempl::Scalar(Scalar::Item(item))),
             },
             StmtSyn::Expr(expr) => {
                 self.stack_mut().push(StmtTempl::Scalar(Scalar::Expr { expr, delim, }));
@@ -135,14 +525,43 @@ impl Visitor<'_> for SynVisitor<'_> {
             // ===== open scope =====
 
             StmtSyn::Block(templ) => {
+                if self.scopes.iter().any(|scope| matches!(scope, Scope::Macro { .. })) {
+                    error!("cannot define `block` in `macro` scope")
+                }
                 self.scopes.push(Scope::Block { templ, stmts: vec![] });
             },
+            StmtSyn::Macro(templ) => {
+                if !self.is_toplevel()
+                    && let Some(scope) = self.scopes.last()
+                {
+                    error!("cannot define `macro` in `{scope}` scope")
+                }
+                self.scopes.push(Scope::Macro { templ, stmts: vec![] });
+            },
             StmtSyn::If(templ) => {
                 self.scopes.push(Scope::If { templ, stmts: vec![], else_branch: None, });
             },
             StmtSyn::For(templ) => {
                 self.scopes.push(Scope::For { templ, stmts: vec![], else_branch: None, });
             },
+            StmtSyn::While(templ) => {
+                self.scopes.push(Scope::While { templ, stmts: vec![] });
+            },
+            StmtSyn::Loop(templ) => {
+                self.scopes.push(Scope::Loop { templ, stmts: vec![] });
+            },
+            StmtSyn::Match(templ) => {
+                self.scopes.push(Scope::Match { templ, arms: vec![] });
+            },
+            StmtSyn::With(templ) => {
+                self.scopes.push(Scope::With { templ, stmts: vec![], else_branch: None });
+            },
+            StmtSyn::Capture(templ) => {
+                self.scopes.push(Scope::Capture { templ, stmts: vec![] });
+            },
+            StmtSyn::Slot(templ) => {
+                self.scopes.push(Scope::Slot { templ, stmts: vec![] });
+            },
 
             // ===== else / intermediate scope =====
 
@@ -162,10 +581,10 @@ impl Visitor<'_> for SynVisitor<'_> {
                     }
                 }
 
-                match self.scopes.pop() {
+                match self.scopes.last_mut() {
                     // else in if scope
-                    Some(Scope::If { templ, stmts, mut else_branch, }) => {
-                        take_latest_else_branch(&mut else_branch)?.replace((
+                    Some(Scope::If { else_branch, .. }) => {
+                        take_latest_else_branch(else_branch)?.replace((
                             else_token,
                             match elif_branch {
                                 Some((if_token, cond)) => Scope::If {
@@ -177,31 +596,42 @@ impl Visitor<'_> for SynVisitor<'_> {
                             }
                             .into(),
                         ));
-
-                        self.scopes.push(Scope::If { templ, stmts, else_branch });
                     }
                     // else in for scope
-                    Some(Scope::For { templ, stmts, mut else_branch, }) => {
-                        let dupl = else_branch.replace(
-                            (else_token, Scope::Root { stmts: vec![] }.into())
-                        );
-                        if dupl.is_some() {
+                    Some(Scope::For { else_branch, .. }) => {
+                        if else_branch.is_some() {
                             error!("cannot have 2 `else` in `for` scope")
                         }
-                        self.scopes.push(Scope::For { templ, stmts, else_branch });
+                        else_branch.replace((else_token, Scope::Root { stmts: vec![] }.into()));
+                    }
+                    // else in with scope
+                    Some(Scope::With { else_branch, .. }) => {
+                        if elif_branch.is_some() {
+                            error!("cannot have `else if` in `with` scope")
+                        }
+                        if else_branch.is_some() {
+                            error!("cannot have 2 `else` in `with` scope")
+                        }
+                        else_branch.replace((else_token, Scope::Root { stmts: vec![] }.into()));
                     }
                     Some(scope) => error!("cannot close `else` in `{scope}` scope"),
                     None => error!("cannot close `else` in toplevel"),
                 };
             },
 
+            StmtSyn::When(templ) => match self.scopes.last_mut() {
+                Some(Scope::Match { arms, .. }) => arms.push((templ, vec![])),
+                Some(scope) => error!("cannot close `when` in `{scope}` scope"),
+                None => error!("cannot close `when` in toplevel"),
+            },
+
             // ===== close scope =====
 
             StmtSyn::Endblock(_endblock) => {
-                let (templ,stmts) = match self.scopes.pop() {
-                    Some(Scope::Block { templ, stmts }) => (templ,stmts),
-                    Some(scope) => error!("cannot close `endblock` in `{scope}` scope"),
-                    None => error!("cannot close `endblock` in toplevel"),
+                let Scope::Block { templ, stmts } = self.pop_scope("endblock", |scope| {
+                    matches!(scope, Scope::Block { .. })
+                })? else {
+                    unreachable!()
                 };
 
                 let name = templ.name.clone();
@@ -212,36 +642,101 @@ impl Visitor<'_> for SynVisitor<'_> {
                             render_token: <_>::default(),
                             value: RenderValue::Ident(name),
                             block: None,
+                            with: None,
                         },
                     )));
                 }
 
                 self.blocks.push(BlockContent { templ, stmts });
             },
-            StmtSyn::EndIf(_endif) => {
-                let if_scope = match self.scopes.pop() {
-                    Some(templ @ Scope::If { .. }) => templ,
-                    Some(scope) => error!("cannot close `endif` in `{scope}` scope"),
-                    None => error!("cannot close `endif` in toplevel"),
+            StmtSyn::EndMacro(_endmacro) => {
+                let Scope::Macro { templ, stmts } = self.pop_scope("endmacro", |scope| {
+                    matches!(scope, Scope::Macro { .. })
+                })? else {
+                    unreachable!()
                 };
 
+                if self.macros.iter().any(|e| e.templ.name == templ.name) {
+                    error!("duplicate macro `{}`", templ.name)
+                }
+
+                self.macros.push(MacroContent { templ, stmts });
+            },
+            StmtSyn::EndIf(_endif) => {
+                let if_scope = self.pop_scope("endif", |scope| matches!(scope, Scope::If { .. }))?;
+
                 self.stack_mut().push(StmtTempl::Scope(if_scope));
             },
             StmtSyn::EndFor(_endfor) => {
-                let for_scope = match self.scopes.pop() {
-                    Some(templ @ Scope::For { .. }) => templ,
-                    Some(scope) => error!("cannot close `endfor` in `{scope}` scope"),
-                    None => error!("cannot close `endfor` in toplevel"),
-                };
+                let for_scope = self.pop_scope("endfor", |scope| matches!(scope, Scope::For { .. }))?;
 
                 self.stack_mut().push(StmtTempl::Scope(for_scope));
             },
+            StmtSyn::EndWhile(_endwhile) => {
+                let while_scope = self.pop_scope("endwhile", |scope| matches!(scope, Scope::While { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(while_scope));
+            },
+            StmtSyn::EndLoop(_endloop) => {
+                let loop_scope = self.pop_scope("endloop", |scope| matches!(scope, Scope::Loop { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(loop_scope));
+            },
+            StmtSyn::EndMatch(_endmatch) => {
+                let match_scope = self.pop_scope("endmatch", |scope| matches!(scope, Scope::Match { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(match_scope));
+            },
+            StmtSyn::EndWith(_endwith) => {
+                let with_scope = self.pop_scope("endwith", |scope| matches!(scope, Scope::With { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(with_scope));
+            },
+            StmtSyn::EndCapture(_endcapture) => {
+                let capture_scope = self.pop_scope("endcapture", |scope| matches!(scope, Scope::Capture { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(capture_scope));
+            },
+            StmtSyn::EndYield(_endyield) => {
+                let yield_scope = self.pop_scope("endyield", |scope| matches!(scope, Scope::Yield { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(yield_scope));
+            },
+            StmtSyn::EndRender(_endrender) => {
+                let render_scope = self.pop_scope("endrender", |scope| matches!(scope, Scope::Render { .. }))?;
+
+                self.stack_mut().push(StmtTempl::Scope(render_scope));
+            },
+            StmtSyn::EndSlot(_endslot) => {
+                let Scope::Slot { templ, stmts } = self.pop_scope("endslot", |scope| {
+                    matches!(scope, Scope::Slot { .. })
+                })? else {
+                    unreachable!()
+                };
+
+                match self.scopes.last_mut() {
+                    // content for named slot
+                    Some(Scope::Render { slots, .. }) => {
+                        let Some(name) = &templ.name else {
+                            error!("content for unnamed slot is written directly in `render` scope")
+                        };
+                        if slots.iter().any(|(e, _)| e.name.as_ref() == Some(name)) {
+                            error!("duplicate slot `{name}`")
+                        }
+                        slots.push((templ, stmts));
+                    },
+                    // placeholder with default content
+                    _ => self.stack_mut().push(StmtTempl::Scope(Scope::Slot { templ, stmts })),
+                }
+            },
         }
 
         Ok(())
     }
 
     fn finish(mut self) -> Result<Self> {
+        self.close_optional()?;
+
         if let Some(scope) = self.scopes.pop() {
             error!("unclosed `{scope}` scope")
         }
@@ -255,8 +750,17 @@ impl std::fmt::Display for Scope {
         match self {
             Self::Root { .. } => f.write_str("root"),
             Self::Block { .. } => f.write_str("block"),
+            Self::Macro { .. } => f.write_str("macro"),
             Self::If { .. } => f.write_str("if"),
             Self::For { .. } => f.write_str("for"),
+            Self::While { .. } => f.write_str("while"),
+            Self::Capture { .. } => f.write_str("capture"),
+            Self::Render { .. } => f.write_str("render"),
+            Self::Slot { .. } => f.write_str("slot"),
+            Self::Yield { .. } => f.write_str("yield"),
+            Self::With { .. } => f.write_str("with"),
+            Self::Loop { .. } => f.write_str("loop"),
+            Self::Match { .. } => f.write_str("match"),
         }
     }
 }
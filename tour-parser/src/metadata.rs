@@ -1,6 +1,7 @@
 //! The [`Metadata`] struct.
 use std::{borrow::Cow, fs::read_to_string, rc::Rc};
 use syn::*;
+use tour_core::Syntax;
 
 use crate::{
     common::{error, path},
@@ -20,16 +21,18 @@ pub struct Metadata {
     path: Rc<str>,
     source: Option<Rc<str>>,
     reload: Reload,
+    syntax: Syntax,
     block: Option<Ident>,
     kind: TemplKind,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum TemplKind {
     Main,
     MainWrapper,
     Layout,
     Import,
+    Partial,
 }
 
 impl std::fmt::Display for TemplKind {
@@ -39,6 +42,7 @@ impl std::fmt::Display for TemplKind {
             Self::MainWrapper => write!(f, "MainWrapper"),
             Self::Layout => write!(f, "Layout"),
             Self::Import => write!(f, "Import"),
+            Self::Partial => write!(f, "Partial"),
         }
     }
 }
@@ -51,17 +55,30 @@ impl Metadata {
 
     /// Create [`Metadata`] with given path inherited from parent meta.
     ///
+    /// Reload behavior and delimiter syntax are inherited.
+    ///
     /// This will set [`TemplKind`] to [`TemplKind::Import`].
     pub fn clone_as_import(&self, path: impl AsRef<std::path::Path>) -> Metadata {
         Self {
             path: path::resolve_at(path, self.dir_ref()),
             source: None,
             reload: self.reload.clone(),
+            syntax: self.syntax.clone(),
             block: None,
             kind: TemplKind::Import,
         }
     }
 
+    /// Create [`Metadata`] for typed partial with given path inherited from parent meta.
+    ///
+    /// This will set [`TemplKind`] to [`TemplKind::Partial`].
+    pub fn clone_as_partial(&self, path: impl AsRef<std::path::Path>) -> Metadata {
+        Self {
+            kind: TemplKind::Partial,
+            ..self.clone_as_import(path)
+        }
+    }
+
     /// Generate layout [`Metadata`] inherited from parent meta.
     ///
     /// This will set [`TemplKind`] to [`TemplKind::Layout`].
@@ -70,6 +87,7 @@ impl Metadata {
             path: path::resolve_at(layout.path.value(), self.dir_ref()),
             source: None,                // there is no inline layout
             reload: self.reload.clone(), // layout specific reload seems redundant
+            syntax: self.syntax.clone(),
             block: None,                 // allows select block for a layout ?
             kind: TemplKind::Layout,
         }
@@ -115,6 +133,11 @@ impl Metadata {
         &self.reload
     }
 
+    /// Returns delimiter [`Syntax`].
+    pub fn syntax(&self) -> &Syntax {
+        &self.syntax
+    }
+
     /// Returns the [`TemplKind`].
     pub fn kind(&self) -> &TemplKind {
         &self.kind
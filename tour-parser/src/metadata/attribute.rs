@@ -1,10 +1,11 @@
 use std::rc::Rc;
-use syn::{punctuated::Punctuated, *};
+use syn::{punctuated::Punctuated, spanned::Spanned as _, *};
+use tour_core::{Delimiter, Syntax};
 
 use super::{Metadata, Reload};
 use crate::{
     common::{DERIVE_ATTRIBUTE, error, path},
-    config::Config,
+    config::{self, Config},
 };
 
 // ===== Visitor =====
@@ -15,6 +16,7 @@ pub struct AttrVisitor<'a> {
     source: Option<Rc<str>>,
     block: Option<Ident>,
     reload: Option<Reload>,
+    syntax: Syntax,
 }
 
 impl<'a> AttrVisitor<'a> {
@@ -25,6 +27,7 @@ impl<'a> AttrVisitor<'a> {
     /// - path: `#[path = ".." | source = ".."]`
     /// - block: `#[block = <Ident>]`
     /// - reload: `#[path = "debug" | "always" | "never" | <Expr>]`
+    /// - delimiter: `#[<brace | bang | percent | quest | hash> = "<open> <close>" | ""]`
     pub fn parse(attrs: &[Attribute], conf: &'a Config) -> Result<Metadata> {
         let mut visitor = Self {
             conf,
@@ -32,6 +35,7 @@ impl<'a> AttrVisitor<'a> {
             source: None,
             block: None,
             reload: None,
+            syntax: conf.syntax().clone(),
         };
 
         for attr in attrs.iter().filter(|e| e.meta.path().is_ident(DERIVE_ATTRIBUTE)) {
@@ -43,11 +47,18 @@ impl<'a> AttrVisitor<'a> {
             }
         }
 
-        let AttrVisitor { path: Some(path), source, block, reload, .. } = visitor else {
+        let AttrVisitor { path: Some(path), source, block, reload, syntax, .. } = visitor else {
             error!("one of `path`, `root`, or `source` is required")
         };
 
-        Ok(Metadata { path, source, reload: reload.unwrap_or_default(), block, kind: super::TemplKind::Main })
+        Ok(Metadata {
+            path,
+            source,
+            reload: reload.unwrap_or_default(),
+            syntax,
+            block,
+            kind: super::TemplKind::Main,
+        })
     }
 
     fn visit_pair(&mut self, name: Ident, value: Expr) -> Result<()> {
@@ -56,7 +67,10 @@ impl<'a> AttrVisitor<'a> {
             _ if name.eq("source") => self.visit_source(name, value),
             _ if name.eq("block") => self.visit_block(name, value),
             _ if name.eq("reload") => self.visit_reload(name, value),
-            _ => error!(name, "no such key"),
+            _ => match config::delimiter(&name.to_string()) {
+                Some(delim) => self.visit_delimiter(delim, value),
+                None => error!(name, "no such key"),
+            },
         }
     }
 
@@ -99,6 +113,15 @@ impl<'a> AttrVisitor<'a> {
             None => Ok(()),
         }
     }
+
+    fn visit_delimiter(&mut self, delim: Delimiter, value: Expr) -> Result<()> {
+        let Some(pair) = config::syntax_pair(&str_value(&value)?) else {
+            error!(value, "expected `\"<open> <close>\"` or empty string")
+        };
+        let syntax = std::mem::take(&mut self.syntax);
+        self.syntax = config::with_delimiter(syntax, delim, pair);
+        Ok(())
+    }
 }
 
 // ===== Util =====
@@ -4,12 +4,17 @@
 //!
 //! For full ast declaration, see [`ast`][super::ast].
 use std::rc::Rc;
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::{ToTokens, quote};
 use syn::{
     ext::IdentExt as _,
-    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    parse::{Parse, ParseStream, Parser},
     *,
 };
 
+use crate::common::FILTERS;
+
 /// Template statments.
 pub enum StmtSyn {
     // ===== Composition =====
@@ -22,29 +27,71 @@ pub enum StmtSyn {
     Render(RenderTempl),
     /// `{{ yield [block <Ident>] }}`
     Yield(YieldTempl),
+    /// `{{ super }}`
+    Super(Token![super]),
+    /// `{{ call [<Ident>::]<Ident>(<Expr>,*) }}`
+    Call(CallTempl),
 
     // ===== Scoped =====
 
     /// `{{ [pub] [static] block <Ident> }}`
     Block(BlockTempl),
+    /// `{{ macro <Ident>(<Pat>: <Type>,*) }}`
+    Macro(MacroTempl),
     /// `{{ if <Expr> }}`
     If(IfTempl),
     /// `{{ else [if <Expr>] }}`
     Else(ElseTempl),
-    /// `{{ for <Pat> in <Expr> }}`
+    /// `{{ [<Label>] for <Pat> in <Expr> [with <Ident>] }}`
     For(ForTempl),
+    /// `{{ [<Label>] while <Expr> }}`
+    While(WhileTempl),
+    /// `{{ [<Label>] loop }}`
+    Loop(LoopTempl),
+    /// `{{ match <Expr> }}`
+    Match(MatchTempl),
+    /// `{{ when <Pat> [if <Expr>] }}`
+    When(WhenTempl),
+    /// `{{ capture <Ident> }}`
+    Capture(CaptureTempl),
+    /// `{{ slot [<Ident>] }}`
+    Slot(SlotTempl),
+    /// `{{ with <Expr> as <Pat> }}`
+    With(WithTempl),
     /// `{{ endblock }}`
     Endblock(kw::endblock),
+    /// `{{ endmacro }}`
+    EndMacro(kw::endmacro),
     /// `{{ endif }}`
     EndIf(kw::endif),
     /// `{{ endfor }}`
     EndFor(kw::endfor),
+    /// `{{ endmatch }}`
+    EndMatch(kw::endmatch),
+    /// `{{ endwhile }}`
+    EndWhile(kw::endwhile),
+    /// `{{ endloop }}`
+    EndLoop(kw::endloop),
+    /// `{{ endcapture }}`
+    EndCapture(kw::endcapture),
+    /// `{{ endwith }}`
+    EndWith(kw::endwith),
+    /// `{{ endrender }}`
+    EndRender(kw::endrender),
+    /// `{{ endslot }}`
+    EndSlot(kw::endslot),
+    /// `{{ endyield }}`
+    EndYield(kw::endyield),
+    /// `{{ <break | continue> [<Lifetime>] }}`
+    Control(ControlTempl),
 
     // ===== Arbitrary =====
 
+    /// `{{ let <Pat> [: <Type>] = <Expr> [;] }}`
+    Let(Rc<LetTempl>),
     /// `{{ <ItemTempl> }}`
     Item(Rc<ItemTempl>),
-    /// `{{ <Expr> }}`
+    /// `{{ <Expr> [<FilterTempl>]* }}`
     Expr(Rc<Expr>),
 }
 
@@ -66,13 +113,17 @@ pub struct UseTempl {
 pub struct RenderTempl {
     pub render_token: kw::render,
     pub value: RenderValue,
-    pub block: Option<(kw::block,Ident)>
+    pub block: Option<(kw::block,Ident)>,
+    /// typed partial value
+    pub with: Option<(kw::with, Rc<Expr>)>,
 }
 
-/// `<Ident> | "path"`
+/// `<Ident> | "path" | <ExprStruct>`
 pub enum RenderValue {
     Ident(Ident),
     Path(LitStr),
+    /// value which implement `Template`
+    Struct(Rc<ExprStruct>),
 }
 
 /// `{{ yield [block <Ident>] }}`
@@ -81,6 +132,24 @@ pub struct YieldTempl {
     pub block: Option<(kw::block, Ident)>
 }
 
+/// `{{ call [<Ident>::]<Ident>(<Expr>,*) }}`
+pub struct CallTempl {
+    pub call_token: kw::call,
+    /// imported template alias
+    pub alias: Option<(Ident, Token![::])>,
+    pub name: Ident,
+    pub paren_token: token::Paren,
+    pub args: Punctuated<Expr, Token![,]>,
+}
+
+/// `{{ macro <Ident>(<Pat>: <Type>,*) }}`
+pub struct MacroTempl {
+    pub macro_token: Token![macro],
+    pub name: Ident,
+    pub paren_token: token::Paren,
+    pub inputs: Punctuated<PatType, Token![,]>,
+}
+
 /// `{{ [pub] [static] block <Ident> }}`
 pub struct BlockTempl {
     pub pub_token: Option<Token![pub]>,
@@ -101,18 +170,98 @@ pub struct ElseTempl {
     pub elif_branch: Option<(Token![if],Rc<Expr>)>
 }
 
-/// `{{ for <Pat> in <Expr> }}`
+/// `{{ [<Label>] for <Pat> in <Expr> [with <Ident>] }}`
 pub struct ForTempl {
+    pub label: Option<Label>,
     pub for_token: Token![for],
     pub pat: Rc<Pat>,
     pub in_token: Token![in],
     pub expr: Rc<Expr>,
+    /// loop metadata binding
+    pub with: Option<(kw::with, Ident)>,
+}
+
+/// `{{ [<Label>] while <Expr> }}`
+pub struct WhileTempl {
+    pub label: Option<Label>,
+    pub while_token: Token![while],
+    pub cond: Rc<Expr>,
+}
+
+/// `{{ [<Label>] loop }}`
+pub struct LoopTempl {
+    pub label: Option<Label>,
+    pub loop_token: Token![loop],
+}
+
+/// `{{ <break | continue> [<Lifetime>] }}`
+pub enum ControlTempl {
+    Break(Token![break], Option<Lifetime>),
+    Continue(Token![continue], Option<Lifetime>),
+}
+
+/// `{{ match <Expr> }}`
+pub struct MatchTempl {
+    pub match_token: Token![match],
+    pub expr: Rc<Expr>,
+}
+
+/// `{{ when <Pat> [if <Expr>] }}`
+pub struct WhenTempl {
+    pub when_token: kw::when,
+    pub pat: Rc<Pat>,
+    pub guard: Option<(Token![if],Rc<Expr>)>,
+}
+
+/// `{{ capture <Ident> }}`
+pub struct CaptureTempl {
+    pub capture_token: kw::capture,
+    pub name: Ident,
+}
+
+/// `{{ slot [<Ident>] }}`
+pub struct SlotTempl {
+    pub slot_token: kw::slot,
+    pub name: Option<Ident>,
+}
+
+/// `{{ with <Expr> as <Pat> }}`
+pub struct WithTempl {
+    pub with_token: kw::with,
+    pub expr: Rc<Expr>,
+    pub as_token: Token![as],
+    pub pat: Rc<Pat>,
+}
+
+/// `{{ let <Pat> [: <Type>] = <Expr> [;] }}`
+pub struct LetTempl {
+    pub let_token: Token![let],
+    pub pat: Pat,
+    pub eq_token: Token![=],
+    pub expr: Expr,
+    pub semi_token: Token![;],
+}
+
+/// `| [filters::]<Ident>[(<Expr>,*)]`
+pub struct FilterTempl {
+    pub pipe_token: Token![|],
+    /// user provided filter, e.g. `filters::` in `| filters::shout`
+    pub module: Option<(Ident, Token![::])>,
+    pub name: Ident,
+    pub args: Option<(token::Paren, Punctuated<Expr, Token![,]>)>,
 }
 
 /// `{{ <ItemTempl> }}`
 pub enum ItemTempl {
     Use(ItemUse),
     Const(ItemConst),
+    Static(ItemStatic),
+    Fn(ItemFn),
+    Struct(ItemStruct),
+    Enum(ItemEnum),
+    Impl(ItemImpl),
+    Trait(ItemTrait),
+    Type(ItemType),
 }
 
 // ===== Parse implementation =====
@@ -125,17 +274,115 @@ impl Parse for StmtSyn {
             _ if UseTempl::peek(input) => input.parse().map(Self::Use),
             _ if input.peek(kw::render) => input.parse().map(Self::Render),
             _ if input.peek(Token![yield]) => input.parse().map(Self::Yield),
+            _ if input.peek(Token![super]) && !input.peek2(Token![::]) => input.parse().map(Self::Super),
+            _ if CallTempl::peek(input) => input.parse().map(Self::Call),
 
             _ if BlockTempl::peek(input) => input.parse().map(Self::Block),
+            _ if input.peek(Token![macro]) => input.parse().map(Self::Macro),
             _ if input.peek(Token![if]) => input.parse().map(Self::If),
             _ if input.peek(Token![else]) => input.parse().map(Self::Else),
             _ if input.peek(Token![for]) => input.parse().map(Self::For),
+            _ if input.peek(Token![while]) => input.parse().map(Self::While),
+            _ if input.peek(Token![loop]) => input.parse().map(Self::Loop),
+            _ if input.peek(Lifetime) => Self::parse_labelled(input),
+            _ if input.peek(Token![match]) => input.parse().map(Self::Match),
+            _ if WhenTempl::peek(input) => input.parse().map(Self::When),
+            _ if CaptureTempl::peek(input) => input.parse().map(Self::Capture),
+            _ if WithTempl::peek(input) => input.parse().map(Self::With),
+            _ if SlotTempl::peek(input) => input.parse().map(Self::Slot),
             _ if input.peek(kw::endblock) => input.parse().map(Self::Endblock),
+            _ if input.peek(kw::endmacro) => input.parse().map(Self::EndMacro),
             _ if input.peek(kw::endif) => input.parse().map(Self::EndIf),
             _ if input.peek(kw::endfor) => input.parse().map(Self::EndFor),
-
+            _ if input.peek(kw::endmatch) => input.parse().map(Self::EndMatch),
+            _ if input.peek(kw::endwhile) => input.parse().map(Self::EndWhile),
+            _ if input.peek(kw::endloop) => input.parse().map(Self::EndLoop),
+            _ if input.peek(kw::endcapture) => input.parse().map(Self::EndCapture),
+            _ if input.peek(kw::endwith) => input.parse().map(Self::EndWith),
+            _ if input.peek(kw::endrender) => input.parse().map(Self::EndRender),
+            _ if input.peek(kw::endslot) => input.parse().map(Self::EndSlot),
+            _ if input.peek(kw::endyield) => input.parse().map(Self::EndYield),
+            _ if input.peek(Token![break]) => input.parse().map(Self::Control),
+            _ if input.peek(Token![continue]) => input.parse().map(Self::Control),
+
+            _ if input.peek(Token![let]) => input.parse().map(Rc::new).map(Self::Let),
             _ if ItemTempl::peek(input) => input.parse().map(Rc::new).map(Self::Item),
-            _ => input.parse().map(Rc::new).map(Self::Expr),
+            _ => Self::parse_filtered(input).map(Rc::new).map(Self::Expr),
+        }
+    }
+}
+
+impl StmtSyn {
+    /// `<Label> <for | while | loop>`
+    fn parse_labelled(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        fork.parse::<Label>()?;
+        let look = fork.lookahead1();
+        match () {
+            _ if look.peek(Token![for]) => input.parse().map(Self::For),
+            _ if look.peek(Token![while]) => input.parse().map(Self::While),
+            _ if look.peek(Token![loop]) => input.parse().map(Self::Loop),
+            _ => Err(look.error()),
+        }
+    }
+
+    /// `<Expr> [<FilterTempl>]*`
+    ///
+    /// Only a lone `|` outside of any group is a pipe, so `||`, `|=` and closures in
+    /// arguments are left untouched. Filters are applied to the whole preceding expression.
+    ///
+    /// A pipe which is not a builtin or `filters::` filter is a bitwise or, e.g. `{{ a | b }}`.
+    fn parse_filtered(input: ParseStream) -> Result<Expr> {
+        let mut parts = vec![TokenStream::new()];
+        let mut joint = false;
+
+        while !input.is_empty() {
+            let token: TokenTree = input.parse()?;
+            let is_pipe = !joint
+                && matches!(&token, TokenTree::Punct(p) if p.as_char() == '|' && p.spacing() == Spacing::Alone);
+            joint = matches!(&token, TokenTree::Punct(p) if p.spacing() == Spacing::Joint);
+
+            if is_pipe {
+                parts.push(TokenStream::new());
+            }
+            parts.last_mut().expect("non empty").extend([token]);
+        }
+
+        let mut parts = parts.into_iter();
+        let mut tokens = parts.next().expect("non empty");
+        for part in parts {
+            match syn::parse2::<FilterTempl>(part.clone()) {
+                Ok(filter) if filter.is_known() => {
+                    let expr = syn::parse2(tokens)?;
+                    tokens = filter.apply(expr).into_token_stream();
+                },
+                _ => tokens.extend(part),
+            }
+        }
+
+        syn::parse2(tokens)
+    }
+}
+
+impl FilterTempl {
+    /// Returns `true` if filter is builtin or from the user provided `filters` module.
+    pub fn is_known(&self) -> bool {
+        match &self.module {
+            Some((module, _)) => module == "filters",
+            None => FILTERS.contains(&&*self.name.to_string()),
+        }
+    }
+
+    /// Generate the filter function call, with reference to `value` as the first argument.
+    pub fn apply(self, value: Expr) -> Expr {
+        let name = &self.name;
+        let path = match &self.module {
+            Some((module, colon)) => quote! { #module #colon #name },
+            None => quote! { ::tour::filters::#name },
+        };
+        let args = self.args.into_iter().flat_map(|(_, args)| args);
+        parse_quote_spanned! {name.span()=>
+            #path(&(#value), #(#args),*)
         }
     }
 }
@@ -146,6 +393,12 @@ impl UseTempl {
     }
 }
 
+impl CallTempl {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(kw::call) && input.peek2(Ident)
+    }
+}
+
 impl BlockTempl {
     pub fn peek(input: ParseStream) -> bool {
         (input.peek(Token![pub]) && input.peek2(Token![static]) && input.peek3(kw::block)) ||
@@ -155,10 +408,58 @@ impl BlockTempl {
     }
 }
 
+impl WhenTempl {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(kw::when)
+    }
+}
+
+impl CaptureTempl {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(kw::capture) && input.peek2(Ident)
+    }
+}
+
+impl SlotTempl {
+    /// Only `slot` or `slot <Ident>` with nothing after it.
+    pub fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<kw::slot>().is_ok()
+            && (fork.is_empty() || fork.call(Ident::parse_any).is_ok() && fork.is_empty())
+    }
+}
+
+impl WithTempl {
+    pub fn peek(input: ParseStream) -> bool {
+        input.peek(kw::with) && (
+            input.peek2(Ident::peek_any) ||
+            input.peek2(Lit) ||
+            input.peek2(Token![&]) ||
+            input.peek2(Token![*])
+        )
+    }
+}
+
 impl ItemTempl {
+    /// [`ItemTempl::peek`] as a parser, consuming all input.
+    pub fn peek_only(input: ParseStream) -> Result<bool> {
+        let ok = Self::peek(input);
+        input.parse::<TokenStream>()?;
+        Ok(ok)
+    }
+
     pub fn peek(input: ParseStream) -> bool {
         input.peek(Token![use]) ||
-        input.peek(Token![const])
+        input.peek(Token![const]) ||
+        input.peek(Token![static]) ||
+        input.peek(Token![fn]) ||
+        input.peek(Token![struct]) ||
+        input.peek(Token![enum]) ||
+        input.peek(Token![impl]) ||
+        input.peek(Token![trait]) ||
+        input.peek(Token![type]) ||
+        input.peek(Token![pub]) ||
+        input.peek(Token![#])
     }
 }
 
@@ -196,6 +497,11 @@ impl Parse for RenderTempl {
             } else {
                 None
             },
+            with: if input.peek(kw::with) {
+                Some((input.parse()?,input.parse().map(Rc::new)?))
+            } else {
+                None
+            },
         })
     }
 }
@@ -205,6 +511,9 @@ impl Parse for RenderValue {
         let look = input.lookahead1();
         match () {
             _ if look.peek(LitStr) => input.parse().map(Self::Path),
+            _ if input.peek(Ident) && (input.peek2(token::Brace) || input.peek2(Token![::])) => {
+                input.parse().map(Rc::new).map(Self::Struct)
+            },
             _ if look.peek(Ident::peek_any) => input.call(Ident::parse_any).map(Self::Ident),
             _ => Err(look.error()),
         }
@@ -224,6 +533,45 @@ impl Parse for YieldTempl {
     }
 }
 
+impl Parse for CallTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            call_token: input.parse()?,
+            alias: if input.peek2(Token![::]) {
+                Some((input.parse()?,input.parse()?))
+            } else {
+                None
+            },
+            name: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            args: content.parse_terminated(Expr::parse, Token![,])?,
+        })
+    }
+}
+
+impl Parse for MacroTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        Ok(Self {
+            macro_token: input.parse()?,
+            name: input.parse()?,
+            paren_token: parenthesized!(content in input),
+            inputs: content.parse_terminated(
+                |input| {
+                    Ok(PatType {
+                        attrs: vec![],
+                        pat: Box::new(Pat::parse_single(input)?),
+                        colon_token: input.parse()?,
+                        ty: input.parse()?,
+                    })
+                },
+                Token![,],
+            )?,
+        })
+    }
+}
+
 impl Parse for BlockTempl {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
@@ -260,33 +608,253 @@ impl Parse for ElseTempl {
 impl Parse for ForTempl {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
+            label: input.parse()?,
             for_token: input.parse()?,
             // this Pat function that is used by syn parse
             pat: Rc::new(Pat::parse_multi_with_leading_vert(input)?),
             in_token: input.parse()?,
             expr: Rc::new(input.parse()?),
+            with: if input.peek(kw::with) {
+                Some((input.parse()?,input.call(parse_binding)?))
+            } else {
+                None
+            },
         })
     }
 }
 
-impl Parse for ItemTempl {
+impl Parse for WhileTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            label: input.parse()?,
+            while_token: input.parse()?,
+            cond: Rc::new(input.parse()?),
+        })
+    }
+}
+
+impl Parse for LoopTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            label: input.parse()?,
+            loop_token: input.parse()?,
+        })
+    }
+}
+
+impl Parse for ControlTempl {
     fn parse(input: ParseStream) -> Result<Self> {
         let look = input.lookahead1();
         match () {
-            _ if look.peek(Token![use]) => input.parse().map(Self::Use),
-            _ if look.peek(Token![const]) => input.parse().map(Self::Const),
+            _ if look.peek(Token![break]) => Ok(Self::Break(input.parse()?, input.parse()?)),
+            _ if look.peek(Token![continue]) => Ok(Self::Continue(input.parse()?, input.parse()?)),
             _ => Err(look.error()),
         }
     }
 }
 
-mod kw {
+impl ToTokens for ControlTempl {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let label = match self {
+            Self::Break(token, label) => {
+                token.to_tokens(tokens);
+                label
+            }
+            Self::Continue(token, label) => {
+                token.to_tokens(tokens);
+                label
+            }
+        };
+        label.to_tokens(tokens);
+        <Token![;]>::default().to_tokens(tokens);
+    }
+}
+
+impl Parse for MatchTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            match_token: input.parse()?,
+            expr: Rc::new(input.parse()?),
+        })
+    }
+}
+
+impl Parse for WhenTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            when_token: input.parse()?,
+            pat: Rc::new(Pat::parse_multi_with_leading_vert(input)?),
+            guard: if input.peek(Token![if]) {
+                Some((input.parse()?,Rc::new(input.parse()?)))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+impl Parse for CaptureTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            capture_token: input.parse()?,
+            name: input.parse()?,
+        })
+    }
+}
+
+impl Parse for SlotTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            slot_token: input.parse()?,
+            name: if input.is_empty() {
+                None
+            } else {
+                Some(input.call(Ident::parse_any)?)
+            },
+        })
+    }
+}
+
+impl Parse for WithTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let with_token = input.parse()?;
+
+        // `as` is also a cast, so the expression ends at the last `as` outside of any group
+        let mut tokens = vec![];
+        while !input.is_empty() {
+            tokens.push(input.parse::<TokenTree>()?);
+        }
+        let Some(at) = tokens.iter().rposition(|e| matches!(e, TokenTree::Ident(id) if id == "as")) else {
+            return Err(input.error("expected `as`"));
+        };
+        let pat = tokens.split_off(at + 1);
+        let as_token = tokens.pop().expect("`as` found");
+
+        Ok(Self {
+            with_token,
+            expr: Rc::new(syn::parse2(tokens.into_iter().collect())?),
+            as_token: syn::parse2(as_token.into())?,
+            pat: Rc::new(Parser::parse2(Pat::parse_multi_with_leading_vert, pat.into_iter().collect())?),
+        })
+    }
+}
+
+impl Parse for LetTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let let_token = input.parse()?;
+        let pat = Pat::parse_single(input)?;
+        let pat = match input.peek(Token![:]) {
+            true => Pat::Type(PatType {
+                attrs: vec![],
+                pat: Box::new(pat),
+                colon_token: input.parse()?,
+                ty: input.parse()?,
+            }),
+            false => pat,
+        };
+        Ok(Self {
+            let_token,
+            pat,
+            eq_token: input.parse()?,
+            expr: input.parse()?,
+            semi_token: input.parse::<Option<_>>()?.unwrap_or_default(),
+        })
+    }
+}
+
+impl ToTokens for LetTempl {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        self.let_token.to_tokens(tokens);
+        self.pat.to_tokens(tokens);
+        self.eq_token.to_tokens(tokens);
+        self.expr.to_tokens(tokens);
+        self.semi_token.to_tokens(tokens);
+    }
+}
+
+impl Parse for FilterTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            pipe_token: input.parse()?,
+            module: if input.peek2(Token![::]) {
+                Some((input.parse()?, input.parse()?))
+            } else {
+                None
+            },
+            name: input.parse()?,
+            args: if input.peek(token::Paren) {
+                let content;
+                Some((parenthesized!(content in input), content.parse_terminated(Expr::parse, Token![,])?))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+impl Parse for ItemTempl {
+    fn parse(input: ParseStream) -> Result<Self> {
+        match input.parse()? {
+            Item::Use(item) => Ok(Self::Use(item)),
+            Item::Const(item) => Ok(Self::Const(item)),
+            Item::Static(item) => Ok(Self::Static(item)),
+            Item::Fn(item) => Ok(Self::Fn(item)),
+            Item::Struct(item) => Ok(Self::Struct(item)),
+            Item::Enum(item) => Ok(Self::Enum(item)),
+            Item::Impl(item) => Ok(Self::Impl(item)),
+            Item::Trait(item) => Ok(Self::Trait(item)),
+            Item::Type(item) => Ok(Self::Type(item)),
+            item => Err(Error::new_spanned(item, "unsupported item in template")),
+        }
+    }
+}
+
+impl ToTokens for ItemTempl {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            Self::Use(item) => item.to_tokens(tokens),
+            Self::Const(item) => item.to_tokens(tokens),
+            Self::Static(item) => item.to_tokens(tokens),
+            Self::Fn(item) => item.to_tokens(tokens),
+            Self::Struct(item) => item.to_tokens(tokens),
+            Self::Enum(item) => item.to_tokens(tokens),
+            Self::Impl(item) => item.to_tokens(tokens),
+            Self::Trait(item) => item.to_tokens(tokens),
+            Self::Type(item) => item.to_tokens(tokens),
+        }
+    }
+}
+
+/// Parse identifier, `loop` keyword is allowed as raw identifier.
+fn parse_binding(input: ParseStream) -> Result<Ident> {
+    let ident = input.call(Ident::parse_any)?;
+    match ident == "loop" {
+        true => Ok(Ident::new_raw("loop", ident.span())),
+        false => Ok(ident),
+    }
+}
+
+pub(crate) mod kw {
     syn::custom_keyword!(layout);
     syn::custom_keyword!(extends);
     syn::custom_keyword!(block);
     syn::custom_keyword!(render);
     syn::custom_keyword!(endblock);
+    syn::custom_keyword!(call);
+    syn::custom_keyword!(endmacro);
     syn::custom_keyword!(endif);
     syn::custom_keyword!(endfor);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(when);
+    syn::custom_keyword!(endmatch);
+    syn::custom_keyword!(endwhile);
+    syn::custom_keyword!(endloop);
+    syn::custom_keyword!(capture);
+    syn::custom_keyword!(endcapture);
+    syn::custom_keyword!(endwith);
+    syn::custom_keyword!(endrender);
+    syn::custom_keyword!(slot);
+    syn::custom_keyword!(endslot);
+    syn::custom_keyword!(endyield);
 }
 
@@ -120,6 +120,33 @@ impl<D: fmt::Display> fmt::Display for Display<D> {
 
 deref!(Display);
 
+/// Wrap [`TemplDisplay`] to skip escaping.
+///
+/// Returned by the `safe` filter.
+#[derive(Debug)]
+pub struct Safe<D>(pub D);
+
+impl<D: TemplDisplay> TemplDisplay for Safe<D> {
+    fn display(&self, f: &mut impl TemplWrite) -> Result<()> {
+        self.0.display(&mut Raw(f))
+    }
+}
+
+deref!(Safe);
+
+/// Forward [`TemplWrite::write_str`] to [`TemplWrite::write_raw`].
+struct Raw<W>(W);
+
+impl<W: TemplWrite> TemplWrite for Raw<W> {
+    fn write_str(&mut self, value: &str) -> Result<()> {
+        self.0.write_raw(value)
+    }
+
+    fn write_raw(&mut self, value: &str) -> Result<()> {
+        self.0.write_raw(value)
+    }
+}
+
 /// Wrap [`fmt::Debug`] to [`TemplDisplay`].
 pub struct Debug<D>(pub D);
 
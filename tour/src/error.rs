@@ -1,5 +1,5 @@
 use std::{fmt, io};
-use tour_core::ParseError;
+use tour_core::{ParseError, ReadError};
 
 /// [`Result`][std::result::Result] alias for [`Error`].
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -43,6 +43,15 @@ impl From<ParseError> for Error {
     }
 }
 
+impl From<ReadError> for Error {
+    fn from(value: ReadError) -> Self {
+        match value {
+            ReadError::Parse(err) => Self::Parse(err),
+            ReadError::Io(err) => Self::Io(err),
+        }
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(value: io::Error) -> Self {
         Self::Io(value)
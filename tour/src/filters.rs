@@ -0,0 +1,58 @@
+//! Builtin filters for `{{ value | filter }}`.
+//!
+//! A filter is a function which receive a reference to the value as the first argument,
+//! followed by the filter arguments, `{{ name | truncate(20) }}` is generated as
+//! `truncate(&name, 20)`.
+//!
+//! User provided filter is prefixed with `filters::`, and resolved from a `filters` module in
+//! scope of the derive macro.
+//!
+//! ```ignore
+//! mod filters {
+//!     pub fn shout(value: &impl std::fmt::Display) -> String {
+//!         format!("{value}!")
+//!     }
+//! }
+//!
+//! #[derive(Template)]
+//! #[template(source = "{{ name | upper | filters::shout }}")]
+//! struct Greet {
+//!     name: String,
+//! }
+//! ```
+//!
+//! Pipe which is not followed by a builtin or `filters::` filter is a bitwise or, e.g.
+//! `{{ a | b }}`.
+use std::fmt::Display;
+
+use crate::{Safe, TemplDisplay};
+
+/// Convert to uppercase.
+pub fn upper<T: Display + ?Sized>(value: &T) -> String {
+    value.to_string().to_uppercase()
+}
+
+/// Convert to lowercase.
+pub fn lower<T: Display + ?Sized>(value: &T) -> String {
+    value.to_string().to_lowercase()
+}
+
+/// Remove leading and trailing whitespace.
+pub fn trim<T: Display + ?Sized>(value: &T) -> String {
+    value.to_string().trim().to_owned()
+}
+
+/// Truncate to at most `len` characters, append `...` if truncated.
+pub fn truncate<T: Display + ?Sized>(value: &T, len: usize) -> String {
+    let mut value = value.to_string();
+    if let Some((i, _)) = value.char_indices().nth(len) {
+        value.truncate(i);
+        value.push_str("...");
+    }
+    value
+}
+
+/// Render the value without escaping.
+pub fn safe<T: TemplDisplay + ?Sized>(value: &T) -> Safe<&T> {
+    Safe(value)
+}
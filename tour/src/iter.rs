@@ -0,0 +1,70 @@
+//! Loop metadata for `{{ for .. with loop }}`.
+use std::iter::{FusedIterator, Peekable};
+
+/// Metadata of the current iteration in `{{ for .. with loop }}`.
+///
+/// ```html
+/// {{ for user in users with loop }}
+///     {{ loop.index }}. {{ user }}{{ if !loop.last }},{{ endif }}
+/// {{ endfor }}
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Loop {
+    /// Iteration count, starting from 1.
+    pub index: usize,
+    /// Iteration count, starting from 0.
+    pub index0: usize,
+    /// Is this the first iteration.
+    pub first: bool,
+    /// Is this the last iteration.
+    pub last: bool,
+    /// Total number of iterations, if known from [`Iterator::size_hint`].
+    pub len: Option<usize>,
+}
+
+impl Loop {
+    /// Wrap an iterator to yield each item with its [`Loop`] metadata.
+    pub fn iter<I: IntoIterator>(iter: I) -> LoopIter<I::IntoIter> {
+        let iter = iter.into_iter();
+        let len = match iter.size_hint() {
+            (min, Some(max)) if min == max => Some(min),
+            _ => None,
+        };
+        LoopIter { iter: iter.peekable(), index0: 0, len }
+    }
+}
+
+/// Iterator returned from [`Loop::iter`].
+pub struct LoopIter<I: Iterator> {
+    iter: Peekable<I>,
+    index0: usize,
+    len: Option<usize>,
+}
+
+impl<I: Iterator> Iterator for LoopIter<I> {
+    type Item = (I::Item, Loop);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let index0 = self.index0;
+        self.index0 += 1;
+
+        // `last` is detected by peeking, so it works for any iterator
+        let last = self.iter.peek().is_none();
+        let meta = Loop {
+            index: index0 + 1,
+            index0,
+            first: index0 == 0,
+            last,
+            len: self.len,
+        };
+
+        Some((item, meta))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: FusedIterator> FusedIterator for LoopIter<I> {}
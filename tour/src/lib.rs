@@ -46,16 +46,23 @@ mod template;
 mod write;
 mod display;
 mod error;
+mod iter;
+pub mod filters;
+#[doc(hidden)]
+pub mod with;
+#[doc(hidden)]
+pub mod partial;
 
 pub use template::Template;
 pub use write::{TemplWrite, Escape, FmtTemplWrite, IoTemplWrite, TemplWriteFmt, TemplWriteIo};
-pub use display::{TemplDisplay, Display, Debug};
+pub use display::{TemplDisplay, Display, Debug, Safe};
 pub use error::{Error, Result};
+pub use iter::{Loop, LoopIter};
 
 #[cfg(feature = "time")]
 pub mod time;
 
 #[doc(no_inline)]
-pub use tour_core::{Parser, StaticVisitor};
+pub use tour_core::{Delimiter, OwnedStaticVisitor, Parser, StaticVisitor, StreamParser, Syntax};
 #[doc(no_inline)]
 pub use tour_macros::Template;
@@ -0,0 +1,54 @@
+//! Typed partial rendering, used by generated code.
+//!
+//! A typed partial only sees the value it is rendered with. Names from the caller are not
+//! visible, so the following fails to compile:
+//!
+//! ```compile_fail
+//! use tour::Template;
+//!
+//! #[derive(Template)]
+//! #[template(source = "{{ render \"/tour/tests/partials/secret.html\" with title }}")]
+//! struct Page {
+//!     title: &'static str,
+//!     secret: &'static str,
+//! }
+//! ```
+use crate::Result;
+
+/// Function rendering a typed partial.
+///
+/// `C` is the enclosing partials, so a recursive partial can render itself, or any partial it is
+/// rendered from.
+pub type Render<T, W, C> = fn(Partial<T, W, C>, C, &T, &mut W) -> Result<()>;
+
+/// Typed partial which is being rendered.
+pub struct Partial<T: ?Sized, W: ?Sized, C> {
+    render: Render<T, W, C>,
+}
+
+impl<T: ?Sized, W: ?Sized, C> Partial<T, W, C> {
+    /// Render the partial with another value.
+    pub fn render(self, ctx: C, value: &T, writer: &mut W) -> Result<()> {
+        (self.render)(self, ctx, value, writer)
+    }
+}
+
+impl<T: ?Sized, W: ?Sized, C> Clone for Partial<T, W, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, W: ?Sized, C> Copy for Partial<T, W, C> { }
+
+/// Render the partial `value` with `render`.
+///
+/// `render` is a function pointer instead of a closure, so it cannot capture the caller.
+pub fn render<T: ?Sized, W: ?Sized, C>(
+    value: &T,
+    writer: &mut W,
+    ctx: C,
+    render: Render<T, W, C>,
+) -> Result<()> {
+    Partial { render }.render(ctx, value, writer)
+}
@@ -0,0 +1,60 @@
+//! Support for `{{ with <Expr> as <Pat> }}`.
+//!
+//! The expression is borrowed, [`Option`] is matched by its content so `None` skips the body,
+//! any other type is always bound. A borrowed [`Option`], such as a destructured field, is also
+//! matched by its content.
+//!
+//! Dispatch is resolved by method autoref, [`WithOption`] is implemented on [`With`] and is
+//! found first, [`WithValue`] is implemented on `&With` and is found otherwise.
+//!
+//! ```
+//! use tour::with::{With, WithOption as _, WithValue as _};
+//!
+//! let value = Some(1);
+//! assert_eq!((&With(&value)).with_kind().with(&value), Some(&1));
+//!
+//! let value = &Some(1);
+//! assert_eq!((&With(&value)).with_kind().with(&value), Some(&1));
+//!
+//! let value = 1;
+//! assert_eq!((&With(&value)).with_kind().with(&value), Some(&1));
+//! ```
+
+/// Borrowed `with` expression.
+pub struct With<'a, T: ?Sized>(pub &'a T);
+
+/// `with` expression is an [`Option`].
+pub trait WithOption {
+    fn with_kind(&self) -> OptionKind {
+        OptionKind
+    }
+}
+
+impl<T> WithOption for With<'_, Option<T>> {}
+
+impl<T> WithOption for With<'_, &Option<T>> {}
+
+/// `with` expression is any other type.
+pub trait WithValue {
+    fn with_kind(&self) -> ValueKind {
+        ValueKind
+    }
+}
+
+impl<T: ?Sized> WithValue for &With<'_, T> {}
+
+pub struct OptionKind;
+
+impl OptionKind {
+    pub fn with<T>(self, value: &Option<T>) -> Option<&T> {
+        value.as_ref()
+    }
+}
+
+pub struct ValueKind;
+
+impl ValueKind {
+    pub fn with<T: ?Sized>(self, value: &T) -> Option<&T> {
+        Some(value)
+    }
+}
@@ -5,12 +5,23 @@ use crate::Result;
 pub trait TemplWrite {
     /// render a buffer with escapes
     fn write_str(&mut self, value: &str) -> Result<()>;
+
+    /// render a buffer without escapes
+    ///
+    /// writer that escapes its input should bypass it here, used by [`Safe`][crate::Safe]
+    fn write_raw(&mut self, value: &str) -> Result<()> {
+        self.write_str(value)
+    }
 }
 
 impl<R> TemplWrite for &mut R where R: TemplWrite {
     fn write_str(&mut self, value: &str) -> Result<()> {
         R::write_str(self, value)
     }
+
+    fn write_raw(&mut self, value: &str) -> Result<()> {
+        R::write_raw(self, value)
+    }
 }
 
 impl TemplWrite for Vec<u8> {
@@ -44,13 +55,8 @@ pub struct Escape<W>(pub W);
 impl<W> TemplWrite for Escape<W> where W: TemplWrite {
     fn write_str(&mut self, value: &str) -> Result<()> {
         let mut latest = 0;
-        let mut iter = value.char_indices();
-
-        loop {
-            let Some((i,ch)) = iter.next() else {
-                break;
-            };
 
+        for (i,ch) in value.char_indices() {
             let escaped = match ch {
                 '&' => "&amp",
                 '<' => "&lt",
@@ -66,14 +72,18 @@ impl<W> TemplWrite for Escape<W> where W: TemplWrite {
             latest = i + 1;
         }
 
-        if let Some(value) = value.get(latest..) {
-            if !value.is_empty() {
-                self.0.write_str(value)?;
-            }
+        if let Some(value) = value.get(latest..)
+            && !value.is_empty()
+        {
+            self.0.write_str(value)?;
         }
 
         Ok(())
     }
+
+    fn write_raw(&mut self, value: &str) -> Result<()> {
+        self.0.write_raw(value)
+    }
 }
 
 macro_rules! deref {
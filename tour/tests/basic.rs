@@ -172,6 +172,40 @@ fn iteration() {
     assert_eq!(max, templ.render().unwrap().len() - dynamic_size);
 }
 
+#[test]
+fn iteration_else_any_iterator() {
+    #[derive(Template)]
+    #[template(source = "{{ for n in numbers.iter().filter(|n| **n > *min) }}{{ n }} {{ else }}None {{ endfor }}
+        {{- for name in names.clone() with loop }}{{ loop.index }}.{{ name }} {{ else }}Empty{{ endfor }}")]
+    struct It {
+        min: u32,
+        numbers: Vec<u32>,
+        names: Vec<String>,
+    }
+
+    let t1 = It { min: 2, numbers: vec![1, 2, 3, 4], names: vec!["A".into(), "B".into()] };
+    assert_eq!(t1.render().unwrap(), "3 4 1.A 2.B ");
+
+    let t2 = It { min: 4, numbers: vec![1, 2, 3, 4], names: vec![] };
+    assert_eq!(t2.render().unwrap(), "None Empty");
+}
+
+#[test]
+fn iteration_metadata() {
+    #[derive(Template)]
+    #[template(source = "{{ for user in users with loop }}{{ if loop.first }}[{{ endif }}
+        {{- loop.index }}/{{ loop.len }}:{{ user }}{{ if !loop.last }}, {{ else }}]{{ endif }}{{ endfor }}
+        {{- for n in numbers.iter().filter(|n| **n % 2 == 0) with loop }}
+        {{- loop.index0 }}={{ n }}{{ if !loop.last }}, {{ endif }}{{ endfor }}")]
+    struct It {
+        users: Vec<&'static str>,
+        numbers: Vec<u32>,
+    }
+
+    let templ = It { users: vec!["A","B","C"], numbers: vec![1,2,3,4,5] };
+    assert_eq!(templ.render().unwrap(), "[1/3:A, 2/3:B, 3/3:C]0=2, 1=4");
+}
+
 #[test]
 fn iteration_2_branch() {
     #[derive(Template)]
@@ -191,6 +225,95 @@ fn iteration_2_branch() {
     assert_eq!(min, t2.render().unwrap().len());
 }
 
+#[test]
+fn loop_control() {
+    #[derive(Template)]
+    #[template(source = "{{ 'rows: for row in rows }}
+        {{- for n in row }}
+            {{- if *n == 0 }}{{ continue 'rows }}{{ endif }}
+            {{- if *n < 0 }}{{ break 'rows }}{{ endif }}
+            {{- n }} {{ endfor }}| {{ endfor }}
+        {{- let mut it = rows.iter().flatten() }}
+        {{- while let Some(n) = it.next() }}{{ if *n == 0 }}{{ break }}{{ endif }}{{ n }}{{ endwhile }}
+        {{- let mut chars = \"abc\".chars() }}
+        {{- loop }}{{ match chars.next() }}{{ when Some(c) }}{{ c }}{{ when None }}{{ break }}{{ endmatch }}{{ endloop }}")]
+    struct Control {
+        rows: Vec<Vec<i32>>,
+    }
+
+    let templ = Control { rows: vec![vec![1, 2], vec![3, 0, 4], vec![5, -1, 6], vec![7]] };
+
+    assert_eq!(templ.render().unwrap(), "1 2 | 3 5 123abc");
+}
+
+#[test]
+fn loop_control_size_hint() {
+    #[derive(Template)]
+    #[template(source = "{{ for x in xs }}{{ if *x > 0 }}{{ break }}{{ endif }}AAAAAAAAAA{{ else }}BBBBB{{ endfor }}")]
+    struct Control {
+        xs: Vec<i32>,
+    }
+
+    let templ = Control { xs: vec![1] };
+
+    // body may break before writing anything
+    assert_eq!(templ.size_hint(), (0, Some(10)));
+    assert_eq!(templ.render().unwrap(), "");
+}
+
+#[test]
+fn matching() {
+    #[allow(dead_code)]
+    enum Status {
+        Pending,
+        Shipped { days: u32 },
+        Cancelled(&'static str),
+    }
+
+    #[derive(Template)]
+    #[template(source = "<p>{{ match status }}
+        {{ when Status::Pending }}Pending
+        {{- when Status::Shipped { days } if *days > 1 }}Arrive in {{ days }} days
+        {{- when Status::Shipped { .. } }}Arrive tomorrow
+        {{- when Status::Cancelled(reason) }}Cancelled: {{ reason }}
+    {{- endmatch }}</p>")]
+    struct Order {
+        status: Status,
+    }
+
+    let t1 = Order { status: Status::Pending };
+    assert_eq!(t1.render().unwrap(), "<p>Pending</p>");
+
+    let t2 = Order { status: Status::Shipped { days: 3 } };
+    assert_eq!(t2.render().unwrap(), "<p>Arrive in 3 days</p>");
+
+    let t3 = Order { status: Status::Shipped { days: 1 } };
+    assert_eq!(t3.render().unwrap(), "<p>Arrive tomorrow</p>");
+
+    let t4 = Order { status: Status::Cancelled("out of stock") };
+    assert_eq!(t4.render().unwrap(), "<p>Cancelled: out of stock</p>");
+
+    let (min, Some(max)) = t1.size_hint() else { unreachable!() };
+
+    assert_eq!(min, t1.render().unwrap().len());
+    assert_eq!(max, t3.render().unwrap().len());
+}
+
+#[test]
+fn binding() {
+    #[derive(Template)]
+    #[template(source = "{{ let total: u32 = items.iter().map(|e| e.1).sum() }}
+        {{- for item in items }}{{ let (name, price) = item }}{{ name }}: {{ price }}, {{ endfor -}}
+        Total: {{ total }}")]
+    struct Cart {
+        items: Vec<(&'static str, u32)>,
+    }
+
+    let templ = Cart { items: vec![("Apple", 2), ("Milk", 3)] };
+
+    assert_eq!(templ.render().unwrap(), "Apple: 2, Milk: 3, Total: 5");
+}
+
 #[test]
 fn using() {
     #[derive(Template)]
@@ -202,3 +325,199 @@ fn using() {
     assert_eq!(templ.render().unwrap(),"Some(1)");
 }
 
+
+#[test]
+fn comment() {
+    #[derive(Template)]
+    #[template(source = "<div>{# {{ for i in }} }} #}{{ name }}{#- note -#} </div>")]
+    struct Comment {
+        name: &'static str,
+    }
+
+    let templ = Comment { name: "Jane" };
+
+    assert_eq!(templ.render().unwrap(),"<div>Jane</div>");
+}
+
+#[test]
+fn raw() {
+    #[derive(Template)]
+    #[template(source = r#"<div x-data="{ open: false }">{{ raw }}{{ open }}{! x !}{{ endraw }}\{{ name }}</div>"#)]
+    struct Raw {
+        name: &'static str,
+    }
+
+    let templ = Raw { name: "Jane" };
+
+    assert_eq!(templ.render().unwrap(),r#"<div x-data="{ open: false }">{{ open }}{! x !}{{ name }}</div>"#);
+}
+
+#[test]
+fn raw_escaped_backslash() {
+    #[derive(Template)]
+    #[template(source = r#"C:\\{{ dir }} \{{ dir }}"#)]
+    struct Path {
+        dir: &'static str,
+    }
+
+    let templ = Path { dir: "Users" };
+
+    assert_eq!(templ.render().unwrap(),r#"C:\Users {{ dir }}"#);
+}
+
+#[test]
+fn raw_field() {
+    #[derive(Template)]
+    #[template(source = "<p>{{ raw }}</p>{{ if *cooked }} and {{ raw }}{{ endif }}")]
+    struct Raw {
+        raw: &'static str,
+        cooked: bool,
+    }
+
+    let templ = Raw { raw: "sushi", cooked: true };
+
+    assert_eq!(templ.render().unwrap(),"<p>sushi</p> and sushi");
+}
+
+#[test]
+fn filters() {
+    mod filters {
+        pub fn shout(value: &impl std::fmt::Display, mark: char) -> String {
+            format!("{value}{mark}")
+        }
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ name | upper | filters::shout('!') }} {{ html | safe }}{{ html }} {{ name | truncate(2) }} {{ (*flags | 1) }} {{ html.chars().filter(|c| c.is_alphabetic()).count() }}")]
+    struct Filters {
+        name: &'static str,
+        html: String,
+        flags: u8,
+    }
+
+    let templ = Filters { name: "jane", html: "<b>".into(), flags: 2 };
+
+    assert_eq!(templ.render().unwrap(),"JANE! <b>&ltb&gt ja... 3 1");
+}
+
+#[test]
+fn filters_bitwise_or() {
+    #[derive(Template)]
+    #[template(source = "{{ a | b }} {{ a | b | 8 }} {{ a | b | upper }}")]
+    struct Flags {
+        a: u8,
+        b: u8,
+    }
+
+    let templ = Flags { a: 1, b: 2 };
+
+    assert_eq!(templ.render().unwrap(),"3 11 3");
+}
+
+#[test]
+fn capture() {
+    #[derive(Template)]
+    #[template(source = "{{ capture title }}{{ for n in names }}{{ n }} {{ endfor }}&{{ endcapture }}<title>{{ title }}</title><meta content=\"{{ title }}\">{{ title.len() }}")]
+    struct Capture {
+        names: Vec<&'static str>,
+    }
+
+    let templ = Capture { names: vec!["<a>", "b"] };
+
+    assert_eq!(templ.render().unwrap(),"<title>&lta&gt b &</title><meta content=\"&lta&gt b &\">11");
+}
+
+#[test]
+fn capture_field() {
+    #[derive(Template)]
+    #[template(source = "{{ capture }} {{ capture.len() }}")]
+    struct Capture {
+        capture: &'static str,
+    }
+
+    let templ = Capture { capture: "photo" };
+
+    assert_eq!(templ.render().unwrap(),"photo 5");
+}
+
+#[test]
+fn with() {
+    struct Author {
+        name: String,
+        email: Option<String>,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ with author.email as email }}{{ email }}{{ else }}no email{{ endwith }} {{ with author.name.len() as n }}{{ n }}{{ endwith }} {{ with self.author as Author { name, .. } }}{{ name }}{{ endwith }} {{ with nick as n }}<{{ n }}>{{ else }}anon{{ endwith }}")]
+    struct With {
+        author: Author,
+        nick: Option<&'static str>,
+    }
+
+    let templ = With { author: Author { name: "Jane".into(), email: None }, nick: None };
+    assert_eq!(templ.render().unwrap(),"no email 4 Jane anon");
+
+    let templ = With {
+        author: Author { name: "Jane".into(), email: Some("jane@mail".into()) },
+        nick: Some("jj"),
+    };
+    assert_eq!(templ.render().unwrap(),"jane@mail 4 Jane <jj>");
+}
+
+#[test]
+fn with_field() {
+    #[derive(Template)]
+    #[template(source = "{{ with }} {{ with.len() }} {{ with self.with as w }}{{ w }}{{ endwith }}")]
+    struct With {
+        with: &'static str,
+    }
+
+    let templ = With { with: "cheese" };
+
+    assert_eq!(templ.render().unwrap(),"cheese 6 cheese");
+}
+
+#[test]
+fn items() {
+    enum Status {
+        Active,
+        Banned,
+    }
+
+    #[derive(Template)]
+    #[template(source = "\
+        {{ fn badge(s: &Status) -> &'static str { match s { Status::Active => \"ok\", Status::Banned => \"no\" } } }}\
+        {{ struct Pair(u8, u8); }}\
+        {{ impl Pair { fn sum(&self) -> u8 { self.0 + self.1 } } }}\
+        {{ static SEP: &str = \"-\"; }}\
+        {{ type Num = u8; }}\
+        {{ badge(status) }}{{ SEP }}{{ Pair(1, 2).sum() }}\
+        {{ block status }}{{ let n: Num = 4 }}{{ badge(status) }}{{ n }}{{ endblock }}")]
+    struct Items {
+        status: Status,
+    }
+
+    let templ = Items { status: Status::Active };
+    assert_eq!(templ.render().unwrap(),"ok-3ok4");
+
+    let templ = Items { status: Status::Banned };
+    assert_eq!(templ.render_block("status").unwrap(),"no4");
+}
+
+#[test]
+fn items_impl() {
+    #[derive(Template)]
+    #[template(source = "\
+        {{ impl Profile { fn greet(&self) -> String { format!(\"Hi {}\", self.name) } } }}\
+        {{ impl std::fmt::Debug for Profile { \
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { f.write_str(self.name) } \
+        } }}\
+        {{ self.greet() }} {? self ?} {{ block title }}{{ self.greet() }}{{ endblock }}")]
+    struct Profile {
+        name: &'static str,
+    }
+
+    let templ = Profile { name: "Jane" };
+    assert_eq!(templ.render().unwrap(),"Hi Jane Jane Hi Jane");
+    assert_eq!(templ.render_block("title").unwrap(),"Hi Jane");
+}
@@ -38,3 +38,166 @@ fn import_block() {
         "\n\n<p>Title Block</p>\n\n\n<p>Title Block</p>\n\n"
     );
 }
+
+#[test]
+fn import_typed() {
+    struct Item {
+        title: &'static str,
+        body: String,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ for item in items }}{{ render \"/tour/tests/partials/card.html\" with item }}{{ endfor }}")]
+    struct Page {
+        items: Vec<Item>,
+    }
+
+    let templ = Page {
+        items: vec![
+            Item { title: "A", body: "one".into() },
+            Item { title: "B", body: "two".into() },
+        ],
+    };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<div>A: ONE</div><div>B: TWO</div>"
+    );
+}
+
+#[test]
+fn import_value() {
+    #[derive(Template)]
+    #[template(source = "<b>{{ title }}</b>")]
+    struct Card {
+        title: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ for title in titles }}{{ render Card { title } }}{{ endfor }}")]
+    struct Page {
+        titles: Vec<&'static str>,
+    }
+
+    let templ = Page { titles: vec!["A", "B"] };
+    assert_eq!(templ.render().unwrap(), "<b>A</b><b>B</b>");
+}
+
+#[test]
+fn import_slots() {
+    #[derive(Template)]
+    #[template(source = "\
+        {{ render \"/tour/tests/partials/modal.html\" }}\
+            {{ for item in items }}<li>{{ item }}</li>{{ endfor }}\
+            {{ slot footer }}<a>{{ items.len() }} items</a>{{ endslot }}\
+        {{ endrender }}\
+        {{ render \"/tour/tests/partials/modal.html\" }}")]
+    struct Page {
+        title: &'static str,
+        items: Vec<&'static str>,
+    }
+
+    let templ = Page { title: "List", items: vec!["A", "B"] };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<div class=\"modal\"><h1>List</h1><li>A</li><li>B</li>\
+        <footer><a>2 items</a></footer></div>\n\
+        <div class=\"modal\"><h1>List</h1><p>Empty</p>\
+        <footer><button>Close</button></footer></div>\n"
+    );
+}
+
+#[test]
+fn import_slot_field() {
+    #[derive(Template)]
+    #[template(source = "{{ slot.len() }} {{ slot.to_uppercase() }} {{ (slot) }}")]
+    struct Machine {
+        slot: &'static str,
+    }
+
+    let templ = Machine { slot: "coin" };
+    assert_eq!(templ.render().unwrap(), "4 COIN coin");
+}
+
+#[test]
+fn import_recursive() {
+    // rendered only as a typed partial, without its own `Template` implementation
+    struct Node {
+        name: &'static str,
+        children: Vec<Node>,
+    }
+
+    #[derive(Template)]
+    #[template(source = "<ul>{{ render \"/tour/tests/partials/tree.html\" with root }}</ul>")]
+    struct Page {
+        root: Node,
+    }
+
+    let leaf = |name| Node { name, children: vec![] };
+    let templ = Page {
+        root: Node { name: "a", children: vec![leaf("b"), Node { name: "c", children: vec![leaf("d")] }] },
+    };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<ul><li>a<ul><li>b</li>\n<li>c<ul><li>d</li>\n</ul></li>\n</ul></li>\n</ul>"
+    );
+}
+
+#[test]
+fn import_mutual_recursive() {
+    enum Entry {
+        File(&'static str),
+        Dir(Folder),
+    }
+
+    #[derive(Template)]
+    #[template(path = "/tour/tests/partials/folder.html")]
+    struct Folder {
+        name: &'static str,
+        entries: Vec<Entry>,
+    }
+
+    let templ = Folder {
+        name: "src",
+        entries: vec![
+            Entry::File("lib.rs"),
+            Entry::Dir(Folder { name: "bin", entries: vec![Entry::File("main.rs")] }),
+        ],
+    };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<dir>src<file>lib.rs</file>\n<dir>bin<file>main.rs</file>\n</dir>\n\n</dir>\n"
+    );
+}
+
+#[test]
+fn import_mutual_recursive_partial() {
+    enum Entry {
+        File(&'static str),
+        Dir(Folder),
+    }
+
+    struct Folder {
+        name: &'static str,
+        entries: Vec<Entry>,
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ render \"/tour/tests/partials/folder.html\" with root }}")]
+    struct Page {
+        root: Folder,
+    }
+
+    let templ = Page {
+        root: Folder {
+            name: "src",
+            entries: vec![
+                Entry::File("lib.rs"),
+                Entry::Dir(Folder { name: "bin", entries: vec![Entry::File("main.rs")] }),
+            ],
+        },
+    };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<dir>src<file>lib.rs</file>\n<dir>bin<file>main.rs</file>\n</dir>\n\n</dir>\n"
+    );
+}
@@ -25,3 +25,48 @@ fn nested_layout() {
     );
 }
 
+
+#[test]
+fn layout_block_override() {
+    #[derive(Template)]
+    #[template(path = "/tour/tests/layout/blocks.html")]
+    struct Override {
+        name: String,
+    }
+
+    let page = Override { name: "barred".into() }.render().unwrap();
+    assert_eq!(&page[..], "<title>barred - Docs - Site</title><main>Hello barred\n</main>\n<footer>by barred</footer>\n");
+}
+
+#[test]
+fn layout_block_override_nested() {
+    #[derive(Template)]
+    #[template(path = "/tour/tests/layout/blocks_nested.html")]
+    struct Override {
+        name: String,
+    }
+
+    let page = Override { name: "n".into() }.render().unwrap();
+    assert_eq!(&page[..], "<title>Docs - Site</title><main>Hello</main>\n<footer>by n</footer>\n");
+}
+
+#[test]
+fn layout_yield_default() {
+    #[derive(Template)]
+    #[template(path = "/tour/tests/layout/head_block.html")]
+    struct WithHead {
+        name: String,
+    }
+
+    #[derive(Template)]
+    #[template(path = "/tour/tests/layout/head_default.html")]
+    struct WithoutHead {
+        name: String,
+    }
+
+    let page = WithHead { name: "barred".into() }.render().unwrap();
+    assert_eq!(&page[..], "<head><title>barred</title></head><p>barred</p>\n\n");
+
+    let page = WithoutHead { name: "barred".into() }.render().unwrap();
+    assert_eq!(&page[..], "<head><title>Default</title></head><p>barred</p>\n\n");
+}
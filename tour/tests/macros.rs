@@ -0,0 +1,40 @@
+use tour::Template;
+
+#[test]
+fn macros() {
+    #[derive(Template)]
+    #[template(path = "/tour/tests/macros/page.html")]
+    struct Page {
+        items: Vec<&'static str>,
+    }
+
+    let templ = Page { items: vec!["a", "b"] };
+    assert_eq!(
+        templ.render().unwrap(),
+        "\n\n<ul><li>a</li><li>b</li></ul>\n\n<label for=\"email\">Email</label><input name=\"email\">\n\n",
+    );
+}
+
+#[test]
+fn macros_inline() {
+    #[derive(Template)]
+    #[template(source = "{{ macro greet(name: &str, n: usize) }}{{ name }}{{ n }} {{ endmacro }}{{ call greet(name, 1) }}{{ call greet(\"b\", 2) }}")]
+    struct Greet {
+        name: String,
+    }
+
+    let templ = Greet { name: "a".into() };
+    assert_eq!(templ.render().unwrap(), "a1 b2 ");
+}
+
+#[test]
+fn macros_call_field() {
+    #[derive(Template)]
+    #[template(source = "{{ call }} {{ call.len() }}")]
+    struct Call {
+        call: &'static str,
+    }
+
+    let templ = Call { call: "ring" };
+    assert_eq!(templ.render().unwrap(), "ring 4");
+}
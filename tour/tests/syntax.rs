@@ -0,0 +1,33 @@
+use tour::Template;
+
+#[test]
+fn custom_delimiter() {
+    #[derive(Template)]
+    #[template(source = "<p>[[ name ]]</p>{{ name }}", brace = "[[ ]]")]
+    struct Custom {
+        name: &'static str,
+    }
+
+    let templ = Custom { name: "<b>" };
+    assert_eq!(templ.render().unwrap(), "<p>&ltb&gt</p>{{ name }}");
+}
+
+#[test]
+fn custom_delimiter_reload() {
+    #[derive(Template)]
+    #[template(
+        path = "/tour/tests/syntax/page.tex",
+        reload = "always",
+        brace = "<% %>",
+        bang = "<%== %>",
+        hash = "<%# %>",
+        percent = "",
+    )]
+    struct Page {
+        title: &'static str,
+        html: &'static str,
+    }
+
+    let templ = Page { title: "Intro", html: "<b>" };
+    assert_eq!(templ.render().unwrap(), "\\section{Intro}\n\\frac{%}{2} <b>\n");
+}
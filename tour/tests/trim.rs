@@ -0,0 +1,28 @@
+use tour::Template;
+
+#[test]
+fn trim() {
+    #[derive(Template)]
+    #[template(source = "<p>\n  {{- name -}}\n</p> {! name -!} <br> {%- name %}")]
+    struct Trim {
+        name: &'static str,
+    }
+
+    let templ = Trim { name: "Jane" };
+    assert_eq!(templ.render().unwrap(), "<p>Jane</p> Jane<br>Jane");
+}
+
+#[test]
+fn trim_reload() {
+    #[derive(Template)]
+    #[template(path = "/tour/tests/trim/list.html", reload = "always")]
+    struct List {
+        users: Vec<&'static str>,
+    }
+
+    let templ = List { users: vec!["Jane","Willie"] };
+    assert_eq!(
+        templ.render().unwrap(),
+        "<ul>\n  <li>Jane</li>\n  <li>Willie</li>\n</ul>\n"
+    );
+}